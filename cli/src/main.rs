@@ -1,10 +1,48 @@
+use connect_four::board as connect_four_board;
+use connect_four::game as connect_four_game;
 use mcts::mcts_core;
+use mcts::mcts_game;
+use mcts::mcts_game::MCTSGame;
+use mcts::search_config;
+use mcts::search_report;
+use std::env;
+use std::fmt;
 use std::io;
 use std::io::Write;
 use tic_tac_toe::board;
 use tic_tac_toe::game;
+use tic_tac_toe::ultimate;
 
-fn parse_input(input: &String) -> Result<(usize, usize), &'static str> {
+/// Prints the move `game_move` the AI agent picked as `player`, with a summary of the search in `report`
+fn print_report<G: MCTSGame>(
+    player: &str,
+    game_move: &str,
+    report: &search_report::SearchReport<G>,
+) {
+    println!(
+        "Player {} plays {} after {} iterations in {:.0?}: expects {:.2}, principal variation {:?}",
+        player,
+        game_move,
+        report.iterations,
+        report.elapsed,
+        report.root_value,
+        report.principal_variation
+    );
+}
+
+/// Reads one line from stdin after printing `prompt`
+fn read_input(prompt: &str) -> String {
+    print!("{}", prompt);
+    io::stdout().flush().expect("Failed to flush stdout");
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .expect("Failed to read move.");
+    input
+}
+
+fn parse_input(input: &str) -> Result<(usize, usize), &'static str> {
     let parts: Vec<&str> = input.split(',').collect();
 
     if parts.len() != 2 {
@@ -19,8 +57,44 @@ fn parse_input(input: &String) -> Result<(usize, usize), &'static str> {
         _ => Err("Must enter valid non-negative numbers separated by a comma."),
     }
 }
-fn main() {
-    let mut game = game::Game::new();
+
+/// Parses a Tic-Tac-Toe move as row_index, col_index, optionally followed by the mark to place, X or O.
+/// Returns `default_mark` as the mark when none is given
+fn parse_marked_input(
+    input: &str,
+    default_mark: board::Cell,
+) -> Result<(usize, usize, board::Cell), &'static str> {
+    let split_mark = input
+        .rsplit_once(',')
+        .map(|(location, mark)| (location, mark.trim()));
+
+    let (location, mark) = match split_mark {
+        Some((location, "X" | "x")) => (location, board::Cell::X),
+        Some((location, "O" | "o")) => (location, board::Cell::O),
+        _ => (input, default_mark),
+    };
+
+    parse_input(location).map(|(row_index, col_index)| (row_index, col_index, mark))
+}
+
+/// Plays `game` between a human, who types moves on stdin, and the AI agent, who plays as `bot`.
+/// `play_input` parses the human's input and plays it on the game, returning the move played or the reason it
+/// was rejected. `get_player_name` names a player in messages
+fn play_against_bot<G, F>(
+    mut game: G,
+    bot: G::Player,
+    prompt: &str,
+    get_player_name: fn(G::Player) -> &'static str,
+    mut play_input: F,
+) where
+    G: MCTSGame + fmt::Display,
+    F: FnMut(&mut G, &str) -> Result<G::Move, &'static str>,
+{
+    let mut tree = mcts_core::MCTree::new(&game);
+    let config = search_config::SearchConfig {
+        solver: true,
+        ..Default::default()
+    };
     loop {
         println!("{}", game);
 
@@ -29,53 +103,158 @@ fn main() {
             break;
         }
 
-        let player = match game.get_turn() {
-            game::GameTurn::TurnX => "X",
-            game::GameTurn::TurnO => "O",
-        };
+        let player = get_player_name(game.get_turn());
 
-        if player == "O" {
-            let mut best_move = None;
+        if game.get_turn() == bot {
+            let report = tree.search(&config);
+            let game_move = report.best_move.unwrap();
+            print_report(player, &format!("{:?}", game_move), &report);
+            game.play_move(game_move);
+            tree.advance_root(&[game_move]).unwrap();
+            continue;
+        }
+
+        let input = read_input(&format!("Player {}, {}: ", player, prompt));
 
-            while best_move.is_none() {
-                let mctn_root = mcts_core::MCTN::new(&game);
-                best_move = mcts_core::MCTN::think_about_best_move(mctn_root, 1000);
+        match play_input(&mut game, &input) {
+            Ok(game_move) => {
+                tree.advance_root(&[game_move]).unwrap();
+            }
+            Err(e) => {
+                println!("{}", e);
             }
+        }
+    }
+}
 
-            let (row_index, col_index) = best_move.unwrap();
-            game.play(row_index, col_index).unwrap();
-            continue;
+fn get_tic_tac_toe_player_name(player: game::GameTurn) -> &'static str {
+    match player {
+        game::GameTurn::TurnX => "X",
+        game::GameTurn::TurnO => "O",
+    }
+}
+
+/// Describes why a Tic-Tac-Toe move was rejected
+fn describe_tic_tac_toe_error(error: game::GamePlayError) -> &'static str {
+    match error {
+        game::GamePlayError::MarkError(board::BoardMarkError::OutOfBound) => {
+            "Index out of bound. Try again."
+        }
+        game::GamePlayError::MarkError(board::BoardMarkError::NonEmptyCell) => {
+            "Cannot mark a non empty cell. Try again."
+        }
+        game::GamePlayError::DeadBoard => {
+            "Cannot mark a board that already holds a line. Try again."
         }
+        _ => {
+            panic!("Should not get here!");
+        }
+    }
+}
 
-        print!(
-            "Select cell for player {} in format row_index, col_index: ",
-            player
-        );
-        io::stdout().flush().expect("Failed to flush stdout");
-
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read Tic-Tac-Toe move.");
-
-        match parse_input(&input) {
-            Ok((row_index, col_index)) => match game.play(row_index, col_index) {
-                Err(game::GamePlayError::MarkError(board::BoardMarkError::OutOfBound)) => {
-                    println!("Index out of bound. Try again.")
+fn play_tic_tac_toe(rules: game::GameRules) {
+    play_against_bot(
+        game::Game::with_rules(3, 3, 3, rules).unwrap(),
+        game::GameTurn::TurnO,
+        "select cell in format row_index, col_index",
+        get_tic_tac_toe_player_name,
+        |game: &mut game::Game, input| {
+            let (row_index, col_index) = parse_input(input)?;
+            game.play(row_index, col_index)
+                .map_err(describe_tic_tac_toe_error)?;
+            Ok((row_index, col_index))
+        },
+    );
+}
+
+fn play_wild_tic_tac_toe() {
+    play_against_bot(
+        mcts_game::WildGame(game::Game::with_rules(3, 3, 3, game::GameRules::Wild).unwrap()),
+        game::GameTurn::TurnO,
+        "select cell and mark (X or O) in format row_index, col_index, mark",
+        get_tic_tac_toe_player_name,
+        |game: &mut mcts_game::WildGame, input| {
+            let (row_index, col_index, mark) = parse_marked_input(input, game.0.get_mark())?;
+            game.0
+                .play_mark(mark, row_index, col_index)
+                .map_err(describe_tic_tac_toe_error)?;
+            Ok((row_index, col_index, mark))
+        },
+    );
+}
+
+fn play_ultimate_tic_tac_toe() {
+    play_against_bot(
+        ultimate::Game::new(),
+        game::GameTurn::TurnO,
+        "select cell of the 9x9 grid in format row_index, col_index",
+        get_tic_tac_toe_player_name,
+        |game: &mut ultimate::Game, input| {
+            let (row_index, col_index) = parse_input(input)?;
+            game.play(row_index, col_index).map_err(|e| match e {
+                ultimate::GamePlayError::MarkError(board::BoardMarkError::OutOfBound) => {
+                    "Index out of bound. Try again."
+                }
+                ultimate::GamePlayError::MarkError(board::BoardMarkError::NonEmptyCell) => {
+                    "Cannot mark a non empty cell. Try again."
                 }
-                Err(game::GamePlayError::MarkError(board::BoardMarkError::NonEmptyCell)) => {
-                    println!("Cannot mark a non empty cell. Try again.")
+                ultimate::GamePlayError::WrongBoard => {
+                    "Must play in the sub-board you were sent to. Try again."
                 }
-                Ok(()) => {
-                    continue;
+                ultimate::GamePlayError::BoardIsDecided => {
+                    "Cannot play in a sub-board that is already decided. Try again."
                 }
                 _ => {
                     panic!("Should not get here!");
                 }
-            },
-            Err(e) => {
-                println!("{}", e);
-            }
-        }
+            })?;
+            Ok((row_index, col_index))
+        },
+    );
+}
+
+fn play_connect_four() {
+    play_against_bot(
+        connect_four_game::Game::new(),
+        connect_four_game::GameTurn::TurnYellow,
+        "select column",
+        |player| match player {
+            connect_four_game::GameTurn::TurnRed => "Red",
+            connect_four_game::GameTurn::TurnYellow => "Yellow",
+        },
+        |game: &mut connect_four_game::Game, input| {
+            let col_index = input
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| "Must enter a valid non-negative column number.")?;
+            game.play(col_index).map_err(|e| match e {
+                connect_four_game::GamePlayError::DropError(
+                    connect_four_board::BoardDropError::OutOfBound,
+                ) => "Column out of bound. Try again.",
+                connect_four_game::GamePlayError::DropError(
+                    connect_four_board::BoardDropError::FullColumn,
+                ) => "Cannot drop a disc into a full column. Try again.",
+                _ => {
+                    panic!("Should not get here!");
+                }
+            })?;
+            Ok(col_index)
+        },
+    );
+}
+
+fn main() {
+    match env::args().nth(1).as_deref() {
+        None | Some("tic-tac-toe") => play_tic_tac_toe(game::GameRules::Standard),
+        Some("misere-tic-tac-toe") => play_tic_tac_toe(game::GameRules::Misere),
+        Some("wild-tic-tac-toe") => play_wild_tic_tac_toe(),
+        Some("notakto") => play_tic_tac_toe(game::GameRules::Notakto { boards: 3 }),
+        Some("ultimate-tic-tac-toe") => play_ultimate_tic_tac_toe(),
+        Some("connect-four") => play_connect_four(),
+        Some(name) => println!(
+            "Unknown game {}. Available games: tic-tac-toe, misere-tic-tac-toe, wild-tic-tac-toe, notakto, \
+             ultimate-tic-tac-toe, connect-four.",
+            name
+        ),
     }
 }
@@ -0,0 +1,246 @@
+//! Contains functionality for manipulating a Connect Four board
+//!
+//! The board stands upright with 6 rows and 7 columns. Discs are dropped into a column and fall to the lowest
+//! empty cell of that column. Rows are indexed from the top, so the bottom row has index 5.
+
+use std::fmt;
+
+/// Number of rows of the board
+pub const ROWS: usize = 6;
+
+/// Number of columns of the board
+pub const COLS: usize = 7;
+
+/// Directions of the lines going through a cell: horizontal, vertical, diagonal and anti-diagonal
+const LINE_DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// Represents a Connect Four Cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Cell {
+    Red,
+    Yellow,
+    Empty,
+}
+
+/// Represents a 6x7 Connect Four board
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Board {
+    cells: [[Cell; COLS]; ROWS],
+}
+
+/// Represents the possible reasons when failing to drop a disc into a column
+#[derive(Debug, PartialEq, Eq)]
+pub enum BoardDropError {
+    FullColumn,
+    OutOfBound,
+}
+
+impl Board {
+    /// Constructs a new empty Connect Four `Board`
+    pub fn new() -> Self {
+        Board {
+            cells: [[Cell::Empty; COLS]; ROWS],
+        }
+    }
+
+    /// Returns `Cell` at location (`row_index`, `col_index`), or an `Err` if location is out-of-bound
+    pub fn get_cell(&self, row_index: usize, col_index: usize) -> Result<Cell, &'static str> {
+        self.cells
+            .get(row_index)
+            .and_then(|r| r.get(col_index).copied())
+            .ok_or("Board index out of bound.")
+    }
+
+    /// Returns a boolean indicating whether the column `col_index` has no empty cell left. Out-of-bound columns
+    /// count as full
+    pub fn is_column_full(&self, col_index: usize) -> bool {
+        !matches!(self.get_cell(0, col_index), Ok(Cell::Empty))
+    }
+
+    /// Returns a boolean indicating whether every cell of the board holds a disc
+    pub fn is_full(&self) -> bool {
+        (0..COLS).all(|col_index| self.is_column_full(col_index))
+    }
+
+    /// Drops disc `disc` into column `col_index`, where it lands on the lowest empty cell. Returns the index of the
+    /// row the disc landed on, or an `Err` if:
+    /// - column is out-of-bounds, or
+    /// - column is full
+    pub fn drop_disc(&mut self, disc: Cell, col_index: usize) -> Result<usize, BoardDropError> {
+        if col_index >= COLS {
+            return Err(BoardDropError::OutOfBound);
+        }
+
+        match (0..ROWS)
+            .rev()
+            .find(|&row_index| self.cells[row_index][col_index] == Cell::Empty)
+        {
+            Some(row_index) => {
+                self.cells[row_index][col_index] = disc;
+                Ok(row_index)
+            }
+            None => Err(BoardDropError::FullColumn),
+        }
+    }
+
+    /// Returns the number of discs of the same color as the disc at location (`row_index`, `col_index`) in the
+    /// longest unbroken horizontal, vertical or diagonal line going through it. Returns 0 for an empty or
+    /// out-of-bound cell
+    pub fn get_longest_line(&self, row_index: usize, col_index: usize) -> usize {
+        let disc = match self.get_cell(row_index, col_index) {
+            Ok(Cell::Empty) | Err(_) => return 0,
+            Ok(disc) => disc,
+        };
+
+        let count_towards = |row_step: isize, col_step: isize| {
+            let mut count = 0;
+            let (mut row, mut col) = (row_index as isize, col_index as isize);
+
+            loop {
+                row += row_step;
+                col += col_step;
+                if row < 0 || col < 0 {
+                    return count;
+                }
+
+                match self.get_cell(row as usize, col as usize) {
+                    Ok(cell) if cell == disc => count += 1,
+                    _ => return count,
+                }
+            }
+        };
+
+        LINE_DIRECTIONS
+            .iter()
+            .map(|&(row_step, col_step)| {
+                1 + count_towards(row_step, col_step) + count_towards(-row_step, -col_step)
+            })
+            .max()
+            .unwrap()
+    }
+
+    /// Returns a copy of the board mirrored left to right, the only symmetry that gravity allows
+    pub fn get_reflected(&self) -> Board {
+        let mut reflected = self.clone();
+
+        for row in reflected.cells.iter_mut() {
+            row.reverse();
+        }
+
+        reflected
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Board::new()
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.iter() {
+            for cell in row.iter() {
+                let symbol = match cell {
+                    Cell::Red => "R",
+                    Cell::Yellow => "Y",
+                    Cell::Empty => " ",
+                };
+
+                write!(f, "| {} ", symbol)?;
+            }
+
+            writeln!(f, "|")?;
+        }
+
+        for col_index in 0..COLS {
+            write!(f, "  {} ", col_index)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initialize_board() {
+        let b = Board::new();
+        for row_index in 0..ROWS {
+            for col_index in 0..COLS {
+                assert_eq!(b.cells[row_index][col_index], Cell::Empty);
+            }
+        }
+    }
+
+    #[test]
+    fn test_drop_disc_stacks_from_bottom() {
+        let mut b = Board::new();
+        assert_eq!(b.drop_disc(Cell::Red, 3), Ok(5));
+        assert_eq!(b.drop_disc(Cell::Yellow, 3), Ok(4));
+        assert_eq!(b.cells[5][3], Cell::Red);
+        assert_eq!(b.cells[4][3], Cell::Yellow);
+    }
+
+    #[test]
+    fn test_drop_disc_fails_full_column() {
+        let mut b = Board::new();
+        for _ in 0..ROWS {
+            b.drop_disc(Cell::Red, 0).unwrap();
+        }
+
+        assert!(b.is_column_full(0));
+        assert_eq!(
+            b.drop_disc(Cell::Yellow, 0),
+            Err(BoardDropError::FullColumn)
+        );
+    }
+
+    #[test]
+    fn test_drop_disc_fails_oob() {
+        let mut b = Board::new();
+        assert_eq!(
+            b.drop_disc(Cell::Red, COLS),
+            Err(BoardDropError::OutOfBound)
+        );
+        assert!(b.is_column_full(COLS));
+    }
+
+    #[test]
+    fn test_longest_line() {
+        let mut b = Board::new();
+        for col_index in 1..4 {
+            b.drop_disc(Cell::Red, col_index).unwrap();
+        }
+        b.drop_disc(Cell::Yellow, 4).unwrap();
+
+        assert_eq!(b.get_longest_line(5, 2), 3);
+        assert_eq!(b.get_longest_line(5, 4), 1);
+        assert_eq!(b.get_longest_line(4, 2), 0);
+    }
+
+    #[test]
+    fn test_reflect_board() {
+        let mut b = Board::new();
+        b.drop_disc(Cell::Red, 0).unwrap();
+
+        let reflected = b.get_reflected();
+        assert_eq!(reflected.cells[5][COLS - 1], Cell::Red);
+        assert_eq!(reflected.get_reflected(), b);
+    }
+
+    #[test]
+    fn test_display_board() {
+        let mut b = Board::new();
+        b.drop_disc(Cell::Red, 0).unwrap();
+        b.drop_disc(Cell::Yellow, 6).unwrap();
+
+        let display = b.to_string();
+        let lines: Vec<&str> = display.lines().collect();
+        assert_eq!(lines.len(), ROWS + 1);
+        assert_eq!(lines[5], "| R |   |   |   |   |   | Y |");
+        assert_eq!(lines[6], "  0   1   2   3   4   5   6 ");
+    }
+}
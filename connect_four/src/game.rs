@@ -0,0 +1,329 @@
+//! Contains functionality for constructing and playing a Connect Four game
+//!
+//! Players take turns dropping a disc into one of the 7 columns of an upright 6x7 board, and the first player to
+//! line up four discs horizontally, vertically or diagonally wins. Red moves first.
+//!
+//! ## Examples
+//!
+//! ```
+//! use connect_four::game;
+//!
+//! let mut game = game::Game::new();
+//! game.play(3).unwrap(); // Player Red drops a disc into column 3
+//! game.play(3).unwrap(); // Player Yellow drops a disc on top of it
+//! ```
+
+use crate::board;
+use std::fmt;
+
+/// Number of discs in a row needed to win
+pub const WIN_LENGTH: usize = 4;
+
+/// Represents the turn of the current player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameTurn {
+    TurnRed,
+    TurnYellow,
+}
+
+/// Represents the state of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameState {
+    Ongoing,
+    RedWon,
+    YellowWon,
+    Tie,
+}
+
+/// Represents the game objects. Interally, it keeps track of:
+/// - the current board state
+/// - the turn of the current player
+/// - the state of the game (i.e, Ongoing, Red won, Yellow won, tie)
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Game {
+    board: board::Board,
+    turn: GameTurn,
+    state: GameState,
+}
+
+/// Represents the possible reasons when failing to play a move
+#[derive(Debug, PartialEq, Eq)]
+pub enum GamePlayError {
+    DropError(board::BoardDropError),
+    GameIsOver,
+}
+
+impl Game {
+    /// Initializes a new `Game` object
+    pub fn new() -> Self {
+        Game {
+            board: board::Board::new(),
+            turn: GameTurn::TurnRed,
+            state: GameState::Ongoing,
+        }
+    }
+
+    /// Gets the current state of the game
+    pub fn get_state(&self) -> GameState {
+        self.state
+    }
+
+    /// Returns a boolean indicating whether the game is over
+    pub fn is_over(&self) -> bool {
+        !matches!(self.state, GameState::Ongoing)
+    }
+
+    /// Gets the turn of the current player
+    pub fn get_turn(&self) -> GameTurn {
+        self.turn
+    }
+
+    /// Gets the board of the game
+    pub fn get_board(&self) -> &board::Board {
+        &self.board
+    }
+
+    /// Updates the state of the game after the current player dropped a disc that landed at location
+    /// (`row_index`, `col_index`). Only lines going through that disc can have been completed
+    fn update_state(&mut self, row_index: usize, col_index: usize) {
+        if self.is_over() {
+            panic!("Cannot update state when game is terminated!")
+        }
+
+        if self.board.get_longest_line(row_index, col_index) >= WIN_LENGTH {
+            self.state = match self.turn {
+                GameTurn::TurnRed => GameState::RedWon,
+                GameTurn::TurnYellow => GameState::YellowWon,
+            };
+        } else if self.board.is_full() {
+            self.state = GameState::Tie;
+        }
+    }
+
+    /// Plays one turn of Connect Four as the current player by dropping a disc into column `col_index`.
+    /// Returns an `Err` if:
+    /// - column is out-of-bounds, or
+    /// - column is full, or
+    /// - game is terminated (not `Ongoing`)
+    pub fn play(&mut self, col_index: usize) -> Result<(), GamePlayError> {
+        if self.is_over() {
+            return Err(GamePlayError::GameIsOver);
+        }
+
+        let (disc, next_turn) = match self.turn {
+            GameTurn::TurnRed => (board::Cell::Red, GameTurn::TurnYellow),
+            GameTurn::TurnYellow => (board::Cell::Yellow, GameTurn::TurnRed),
+        };
+
+        let row_index = self
+            .board
+            .drop_disc(disc, col_index)
+            .map_err(GamePlayError::DropError)?;
+
+        self.update_state(row_index, col_index);
+        self.turn = next_turn;
+        Ok(())
+    }
+
+    /// Returns a copy of the game state after a disc has been dropped into column `col_index`
+    pub fn get_played(&self, col_index: usize) -> Result<Self, GamePlayError> {
+        let mut cloned_game = (*self).clone();
+        cloned_game.play(col_index)?;
+        Ok(cloned_game)
+    }
+
+    // Returns a vector of possible moves as column indices.
+    // List of moves is always ordered left -> right
+    pub fn get_possible_plays(&self) -> Vec<usize> {
+        if self.is_over() {
+            return Vec::new();
+        }
+
+        (0..board::COLS)
+            .filter(|&col_index| !self.board.is_column_full(col_index))
+            .collect()
+    }
+
+    /// Returns a copy of the game with the board mirrored left to right. Turn and state are left unchanged,
+    /// since mirroring preserves winning lines
+    pub fn get_reflected(&self) -> Self {
+        Game {
+            board: self.board.get_reflected(),
+            turn: self.turn,
+            state: self.state,
+        }
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let game_state = match self.state {
+            GameState::Ongoing => "Ongoing",
+            GameState::RedWon => "Red Won",
+            GameState::YellowWon => "Yellow Won",
+            GameState::Tie => "Tie",
+        };
+
+        let game_turn = match self.turn {
+            GameTurn::TurnRed => "Red",
+            GameTurn::TurnYellow => "Yellow",
+        };
+
+        writeln!(f, "Game State: {}, Player Turn: {}", game_state, game_turn)?;
+        writeln!(f, "\n\n{}", self.board)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays `columns` from an empty board
+    fn game_after(columns: &[usize]) -> Game {
+        let mut game = Game::new();
+        for &col_index in columns {
+            game.play(col_index).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_initialize_game() {
+        let game = Game::new();
+        assert_eq!(game.board, board::Board::new());
+        assert_eq!(game.state, GameState::Ongoing);
+        assert_eq!(game.turn, GameTurn::TurnRed);
+    }
+
+    #[test]
+    fn test_turn_switch() {
+        let mut game = Game::new();
+        game.play(0).unwrap();
+        assert_eq!(game.turn, GameTurn::TurnYellow);
+        game.play(0).unwrap();
+        assert_eq!(game.turn, GameTurn::TurnRed);
+    }
+
+    #[test]
+    fn test_red_won_horizontal() {
+        let mut game = game_after(&[0, 0, 1, 1, 2, 2]);
+        assert_eq!(game.state, GameState::Ongoing);
+
+        game.play(3).unwrap();
+        assert_eq!(game.state, GameState::RedWon);
+    }
+
+    #[test]
+    fn test_yellow_won_vertical() {
+        let mut game = game_after(&[0, 6, 1, 6, 2, 6, 4]);
+        assert_eq!(game.state, GameState::Ongoing);
+
+        game.play(6).unwrap();
+        assert_eq!(game.state, GameState::YellowWon);
+    }
+
+    #[test]
+    fn test_red_won_diagonal() {
+        // Red climbs from (5, 0) to (2, 3)
+        let mut game = game_after(&[0, 1, 1, 2, 2, 3, 2, 3, 3, 6]);
+        assert_eq!(game.state, GameState::Ongoing);
+
+        game.play(3).unwrap();
+        assert_eq!(game.state, GameState::RedWon);
+    }
+
+    #[test]
+    fn test_yellow_won_anti_diagonal() {
+        // Yellow climbs from (5, 6) to (2, 3)
+        let mut game = game_after(&[0, 6, 5, 5, 4, 4, 3, 4, 3, 3, 0]);
+        assert_eq!(game.state, GameState::Ongoing);
+
+        game.play(3).unwrap();
+        assert_eq!(game.state, GameState::YellowWon);
+    }
+
+    #[test]
+    fn test_tie() {
+        // Columns are filled in pairs with alternating blocks of three, so that no line of four ever forms
+        let mut columns = Vec::new();
+        for pair in [[0, 1], [2, 3], [4, 5]] {
+            for _ in 0..3 {
+                columns.extend_from_slice(&pair);
+            }
+            for _ in 0..3 {
+                columns.extend_from_slice(&[pair[1], pair[0]]);
+            }
+        }
+        columns.extend_from_slice(&[6; 5]);
+
+        let mut game = game_after(&columns);
+        assert_eq!(game.state, GameState::Ongoing);
+
+        game.play(6).unwrap();
+        assert_eq!(game.state, GameState::Tie);
+    }
+
+    #[test]
+    fn test_full_column() {
+        let mut game = game_after(&[0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            game.play(0),
+            Err(GamePlayError::DropError(board::BoardDropError::FullColumn))
+        );
+
+        // Ensure that state/turn states don't change after an invalid move
+        assert_eq!(game.state, GameState::Ongoing);
+        assert_eq!(game.turn, GameTurn::TurnRed);
+    }
+
+    #[test]
+    fn test_out_of_bound() {
+        let mut game = Game::new();
+        assert_eq!(
+            game.play(7),
+            Err(GamePlayError::DropError(board::BoardDropError::OutOfBound))
+        );
+        assert_eq!(game.turn, GameTurn::TurnRed);
+    }
+
+    #[test]
+    fn test_play_game_over() {
+        let mut game = game_after(&[0, 0, 1, 1, 2, 2, 3]);
+        assert_eq!(game.play(4), Err(GamePlayError::GameIsOver));
+        assert_eq!(game.state, GameState::RedWon);
+    }
+
+    #[test]
+    fn test_possible_plays() {
+        let game = game_after(&[3, 3, 3, 3, 3, 3]);
+        assert_eq!(game.get_possible_plays(), vec![0, 1, 2, 4, 5, 6]);
+
+        let game = game_after(&[0, 0, 1, 1, 2, 2, 3]);
+        assert!(game.get_possible_plays().is_empty());
+    }
+
+    #[test]
+    fn test_get_played() {
+        let game = game_after(&[2]);
+        let played = game.get_played(2).unwrap();
+
+        assert_eq!(played.board.get_cell(4, 2).unwrap(), board::Cell::Yellow);
+        // The original game is left untouched
+        assert_eq!(game.board.get_cell(4, 2).unwrap(), board::Cell::Empty);
+    }
+
+    #[test]
+    fn test_reflected_game() {
+        let game = game_after(&[0, 1]);
+        let reflected = game.get_reflected();
+
+        assert!(reflected == game_after(&[6, 5]));
+    }
+}
@@ -0,0 +1,92 @@
+//! Measures how much smarter playouts help, by playing Tic-Tac-Toe matches between an MCTS player using each
+//! rollout policy and an MCTS player using uniformly random playouts, both with the same iteration budget.
+//! Players alternate who moves first.
+//!
+//! Run with `cargo run --release -p mcts --example compare_rollouts`
+
+use mcts::{mcts_core, mcts_game::GameOutcome, mcts_game::MCTSGame, rollout_policy, search_config};
+use std::sync;
+use tic_tac_toe::game;
+
+const GAMES: u32 = 200;
+const ITERATIONS: u32 = 200;
+
+fn config_with(
+    rollout_policy: sync::Arc<dyn rollout_policy::RolloutPolicy<game::Game>>,
+    seed: u64,
+) -> search_config::SearchConfig<game::Game> {
+    search_config::SearchConfig {
+        budget: search_config::SearchBudget::Iterations(ITERATIONS),
+        rollout_policy,
+        seed: Some(seed),
+        ..Default::default()
+    }
+}
+
+/// Plays one game and returns its outcome. `candidate_first` tells whether the candidate player moves first
+fn play_game(
+    candidate: &search_config::SearchConfig<game::Game>,
+    baseline: &search_config::SearchConfig<game::Game>,
+    candidate_first: bool,
+) -> GameOutcome<game::GameTurn> {
+    let mut game = game::Game::new();
+    let mut candidate_to_move = candidate_first;
+
+    while !game.is_over() {
+        let config = if candidate_to_move {
+            candidate
+        } else {
+            baseline
+        };
+        let mut tree = mcts_core::MCTree::new(&game);
+        let game_move = tree.think_about_best_move(config).unwrap();
+
+        game.play_move(game_move);
+        candidate_to_move = !candidate_to_move;
+    }
+
+    game.get_outcome().unwrap()
+}
+
+fn main() {
+    let candidates: Vec<(
+        &str,
+        sync::Arc<dyn rollout_policy::RolloutPolicy<game::Game>>,
+    )> = vec![
+        ("uniform", sync::Arc::new(rollout_policy::UniformRollout)),
+        ("tactical", sync::Arc::new(rollout_policy::TacticalRollout)),
+        (
+            "weighted",
+            sync::Arc::new(rollout_policy::WeightedRollout::default()),
+        ),
+    ];
+
+    for (name, policy) in candidates {
+        let (mut wins, mut draws, mut losses) = (0, 0, 0);
+
+        for game_index in 0..GAMES {
+            let candidate_first = game_index % 2 == 0;
+            let candidate = config_with(policy.clone(), 2 * game_index as u64);
+            let baseline = config_with(
+                sync::Arc::new(rollout_policy::UniformRollout),
+                2 * game_index as u64 + 1,
+            );
+            let candidate_turn = if candidate_first {
+                game::GameTurn::TurnX
+            } else {
+                game::GameTurn::TurnO
+            };
+
+            match play_game(&candidate, &baseline, candidate_first) {
+                GameOutcome::Won(winner) if winner == candidate_turn => wins += 1,
+                GameOutcome::Won(_) => losses += 1,
+                GameOutcome::Tie => draws += 1,
+            }
+        }
+
+        println!(
+            "{:>8} vs uniform: {} wins, {} draws, {} losses",
+            name, wins, draws, losses
+        );
+    }
+}
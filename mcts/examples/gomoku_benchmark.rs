@@ -0,0 +1,102 @@
+//! Benchmarks search improvements on Gomoku, where uniformly random playouts on a 15x15 board say little about
+//! a position. Plays matches between an MCTS player using each improvement and a plain UCT player, both with the
+//! same iteration budget and adding one child per iteration. Players alternate who moves first.
+//!
+//! Run with `cargo run --release -p mcts --example gomoku_benchmark`
+
+use mcts::{mcts_core, mcts_game::GameOutcome, mcts_game::MCTSGame, search_config, tree_policy};
+use std::sync;
+use tic_tac_toe::gomoku;
+
+const GAMES: u32 = 6;
+const ITERATIONS: u32 = 1000;
+
+/// Builds the search configuration of a player from a seed
+type ConfigBuilder = fn(u64) -> search_config::SearchConfig<gomoku::Game>;
+
+fn baseline_config(seed: u64) -> search_config::SearchConfig<gomoku::Game> {
+    search_config::SearchConfig {
+        budget: search_config::SearchBudget::Iterations(ITERATIONS),
+        expansion: search_config::ExpansionMode::Single { threshold: 0 },
+        seed: Some(seed),
+        ..Default::default()
+    }
+}
+
+/// Plays one game and returns its outcome. `candidate_first` tells whether the candidate player moves first
+fn play_game(
+    rules: gomoku::GameRules,
+    candidate: &search_config::SearchConfig<gomoku::Game>,
+    baseline: &search_config::SearchConfig<gomoku::Game>,
+    candidate_first: bool,
+) -> GameOutcome<gomoku::Player> {
+    let mut game = gomoku::Game::with_rules(rules);
+    let candidate_player = if candidate_first {
+        gomoku::Player::First
+    } else {
+        gomoku::Player::Second
+    };
+
+    while !game.is_over() {
+        let config = if MCTSGame::get_turn(&game) == candidate_player {
+            candidate
+        } else {
+            baseline
+        };
+        let mut tree = mcts_core::MCTree::new(&game);
+        let game_move = tree.think_about_best_move(config).unwrap();
+
+        game.play_move(game_move);
+    }
+
+    game.get_outcome().unwrap()
+}
+
+fn main() {
+    // Free-style Gomoku with the pro opening, so that the first player cannot start with a tight cluster
+    let rules = gomoku::GameRules {
+        opening: gomoku::Opening::Pro,
+        ..Default::default()
+    };
+
+    let candidates: Vec<(&str, ConfigBuilder)> = vec![
+        ("rave", |seed| search_config::SearchConfig {
+            rave_equivalence: Some(1000.0),
+            ..baseline_config(seed)
+        }),
+        ("solver", |seed| search_config::SearchConfig {
+            solver: true,
+            ..baseline_config(seed)
+        }),
+        ("ucb1-tuned", |seed| search_config::SearchConfig {
+            tree_policy: Some(sync::Arc::new(tree_policy::Ucb1Tuned)),
+            ..baseline_config(seed)
+        }),
+    ];
+
+    for (name, candidate_config) in candidates {
+        let (mut wins, mut draws, mut losses) = (0, 0, 0);
+
+        for game_index in 0..GAMES {
+            let candidate_first = game_index % 2 == 0;
+            let candidate = candidate_config(2 * game_index as u64);
+            let baseline = baseline_config(2 * game_index as u64 + 1);
+            let candidate_player = if candidate_first {
+                gomoku::Player::First
+            } else {
+                gomoku::Player::Second
+            };
+
+            match play_game(rules, &candidate, &baseline, candidate_first) {
+                GameOutcome::Won(winner) if winner == candidate_player => wins += 1,
+                GameOutcome::Won(_) => losses += 1,
+                GameOutcome::Tie => draws += 1,
+            }
+        }
+
+        println!(
+            "{:>10} vs uct: {} wins, {} draws, {} losses",
+            name, wins, draws, losses
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Plays Tic-Tac-Toe matches between a PUCT search guided by a trained `PolicyValueNet` and plain UCT search with
+//! uniformly random playouts, both with the same iteration budget. Players alternate who moves first.
+//!
+//! Run with `cargo run --release -p mcts --example net_vs_uct -- [weights path]` after training a network with
+//! the `train_policy_value_net` example. The path defaults to `policy_value_net.txt`
+
+use mcts::{
+    mcts_core, mcts_game::GameOutcome, mcts_game::MCTSGame, policy_value_net, search_config,
+};
+use std::env;
+use std::path;
+use std::sync;
+use tic_tac_toe::game;
+
+const GAMES: u32 = 200;
+const ITERATIONS: u32 = 100;
+
+/// Plays one game and returns its outcome. `net_first` tells whether the network player moves first
+fn play_game(
+    net_config: &search_config::SearchConfig<game::Game>,
+    uct_config: &search_config::SearchConfig<game::Game>,
+    net_first: bool,
+) -> GameOutcome<game::GameTurn> {
+    let mut game = game::Game::new();
+    let mut net_to_move = net_first;
+
+    while !game.is_over() {
+        let config = if net_to_move { net_config } else { uct_config };
+        let mut tree = mcts_core::MCTree::new(&game);
+        let game_move = tree.think_about_best_move(config).unwrap();
+
+        game.play_move(game_move);
+        net_to_move = !net_to_move;
+    }
+
+    game.get_outcome().unwrap()
+}
+
+fn main() {
+    let weights_path = path::PathBuf::from(
+        env::args()
+            .nth(1)
+            .unwrap_or_else(|| "policy_value_net.txt".to_string()),
+    );
+    let net = sync::Arc::new(
+        policy_value_net::PolicyValueNet::load(&weights_path)
+            .expect("Could not load the network weights."),
+    );
+    let (mut wins, mut draws, mut losses) = (0, 0, 0);
+
+    for game_index in 0..GAMES {
+        let net_first = game_index % 2 == 0;
+        let net_config = search_config::SearchConfig {
+            evaluator: Some(net.clone()),
+            exploration_constant: 1.5,
+            budget: search_config::SearchBudget::Iterations(ITERATIONS),
+            final_move_policy: search_config::FinalMovePolicy::RobustChild,
+            seed: Some(2 * game_index as u64),
+            ..Default::default()
+        };
+        let uct_config = search_config::SearchConfig {
+            budget: search_config::SearchBudget::Iterations(ITERATIONS),
+            seed: Some(2 * game_index as u64 + 1),
+            ..Default::default()
+        };
+        let net_turn = if net_first {
+            game::GameTurn::TurnX
+        } else {
+            game::GameTurn::TurnO
+        };
+
+        match play_game(&net_config, &uct_config, net_first) {
+            GameOutcome::Won(winner) if winner == net_turn => wins += 1,
+            GameOutcome::Won(_) => losses += 1,
+            GameOutcome::Tie => draws += 1,
+        }
+    }
+
+    println!(
+        "net vs uct at {} iterations: {} wins, {} draws, {} losses",
+        ITERATIONS, wins, draws, losses
+    );
+}
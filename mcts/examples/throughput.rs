@@ -0,0 +1,34 @@
+//! Measures MCTS throughput in playouts per second, starting from an empty Tic-Tac-Toe board.
+//!
+//! Run with `cargo run --release -p mcts --example throughput`
+
+use mcts::{mcts_core, search_config};
+use std::time;
+use tic_tac_toe::game;
+
+const SEARCHES: u32 = 200;
+const ITERATIONS: u32 = 1000;
+
+fn main() {
+    let config = search_config::SearchConfig {
+        budget: search_config::SearchBudget::Iterations(ITERATIONS),
+        ..Default::default()
+    };
+
+    let start = time::Instant::now();
+    let mut playouts = 0.0;
+
+    for _ in 0..SEARCHES {
+        let mut tree = mcts_core::MCTree::new(&game::Game::new());
+        tree.think_about_best_move(&config);
+        playouts += tree.get_root_visits();
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    println!(
+        "{} playouts in {:.2}s: {:.0} playouts/s",
+        playouts,
+        elapsed,
+        playouts / elapsed
+    );
+}
@@ -0,0 +1,46 @@
+//! Trains a Tic-Tac-Toe `PolicyValueNet` by self-play, then saves its weights and the self-play samples.
+//!
+//! Run with `cargo run --release -p mcts --example train_policy_value_net -- [weights path] [samples path]`.
+//! The paths default to `policy_value_net.txt` and `self_play_samples.txt`
+//! Evaluate the result against plain UCT with the `net_vs_uct` example.
+
+use mcts::{policy_value_net, self_play};
+use rand::SeedableRng;
+use std::env;
+use std::path;
+use std::time;
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let weights_path = path::PathBuf::from(
+        args.next()
+            .unwrap_or_else(|| "policy_value_net.txt".to_string()),
+    );
+    let samples_path = path::PathBuf::from(
+        args.next()
+            .unwrap_or_else(|| "self_play_samples.txt".to_string()),
+    );
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    let mut net = policy_value_net::PolicyValueNet::new(&[64, 64], &mut rng);
+    let config = self_play::TrainingConfig {
+        samples_path: Some(samples_path),
+        ..Default::default()
+    };
+
+    let start = time::Instant::now();
+    self_play::train(&mut net, &config, &mut rng, |report| {
+        println!(
+            "generation {:>3}: {:>6} samples, loss {:.4}, {:.0?} elapsed",
+            report.generation,
+            report.sample_count,
+            report.loss,
+            start.elapsed()
+        );
+    })
+    .expect("Could not write the self-play samples.");
+
+    net.save(&weights_path)
+        .expect("Could not save the network weights.");
+    println!("Saved the weights to {}", weights_path.display());
+}
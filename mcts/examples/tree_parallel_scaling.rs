@@ -0,0 +1,41 @@
+//! Measures how tree-parallel MCTS throughput, in playouts per second, scales with the number of threads,
+//! starting from an empty Tic-Tac-Toe board.
+//!
+//! Run with `cargo run --release -p mcts --example tree_parallel_scaling`
+
+use mcts::{search_config, shared_tree};
+use std::time;
+use tic_tac_toe::game;
+
+const SEARCHES: u32 = 50;
+const ITERATIONS: u32 = 4000;
+
+fn main() {
+    let available_threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    println!("{} hardware threads available", available_threads);
+
+    for threads in [1, 2, 4, 8] {
+        let config = search_config::SearchConfig {
+            budget: search_config::SearchBudget::Iterations(ITERATIONS),
+            parallelism: search_config::Parallelism::TreeParallel { threads },
+            ..Default::default()
+        };
+        let start = time::Instant::now();
+        let mut playouts = 0.0;
+
+        for _ in 0..SEARCHES {
+            let mut tree = shared_tree::SharedTree::new(&game::Game::new());
+            tree.think_about_best_move(&config);
+            playouts += tree.get_root_visits();
+        }
+
+        let elapsed = start.elapsed().as_secs_f64();
+        println!(
+            "{} threads: {} playouts in {:.2}s: {:.0} playouts/s",
+            threads,
+            playouts,
+            elapsed,
+            playouts / elapsed
+        );
+    }
+}
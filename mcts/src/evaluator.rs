@@ -0,0 +1,129 @@
+//! Contains the evaluators that estimate game states for PUCT-style MCTS (Monte Carlo Tree Search)
+//!
+//! An evaluator replaces the playouts of plain MCTS: it returns a prior probability for every legal move, which
+//! guides selection through the PUCT (Predictor + Upper Confidence bounds applied to Trees) rule, and a value
+//! estimating how well the game goes for the player to move, which is backpropagated instead of a playout result.
+//!
+//! ## Examples
+//!
+//! ```
+//! use mcts::{evaluator, mcts_core, search_config};
+//! use std::sync;
+//! use tic_tac_toe::game;
+//!
+//! let config = search_config::SearchConfig {
+//!     evaluator: Some(sync::Arc::new(evaluator::RolloutEvaluator)),
+//!     exploration_constant: 1.5,
+//!     budget: search_config::SearchBudget::Iterations(200),
+//!     ..Default::default()
+//! };
+//!
+//! let mut tree = mcts_core::MCTree::new(&game::Game::new());
+//! assert!(tree.think_about_best_move(&config).is_some());
+//! ```
+
+use crate::mcts_game::{GameOutcome, MCTSGame};
+use crate::search_config::SearchConfig;
+use rand::RngCore;
+
+/// Represents the estimate of a game state. Includes
+/// - prior probability of every legal move, summing to 1
+/// - value of the game state for the player to move, between 0 (certain loss) and 1 (certain win)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation<M> {
+    pub priors: Vec<(M, f64)>,
+    pub value: f64,
+}
+
+/// Represents an estimator of game states, such as a policy/value network
+pub trait Evaluator<G: MCTSGame>: Send + Sync {
+    /// Evaluates `game`, which is not over. Values are on the same scale as playout rewards in `config`, where
+    /// a tie is worth `config.draw_value`
+    fn evaluate(
+        &self,
+        game: &G,
+        config: &SearchConfig<G>,
+        rng: &mut dyn RngCore,
+    ) -> Evaluation<G::Move>;
+}
+
+/// Gives every legal move the same prior, and values the game state with a single playout driven by the rollout
+/// policy in the search config. Makes PUCT search work without any trained model
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RolloutEvaluator;
+
+impl<G: MCTSGame> Evaluator<G> for RolloutEvaluator {
+    fn evaluate(
+        &self,
+        game: &G,
+        config: &SearchConfig<G>,
+        rng: &mut dyn RngCore,
+    ) -> Evaluation<G::Move> {
+        let possible_plays = game.get_possible_plays();
+        let prior = 1.0 / possible_plays.len() as f64;
+        let priors = possible_plays
+            .iter()
+            .map(|&game_move| (game_move, prior))
+            .collect();
+
+        let player = game.get_turn();
+        let mut cloned_game = game.clone();
+
+        while !cloned_game.is_over() {
+            let possible_plays = cloned_game.get_possible_plays();
+            let rollout_move =
+                config
+                    .rollout_policy
+                    .choose_move(&cloned_game, &possible_plays, &mut *rng);
+            cloned_game.play_move(rollout_move);
+        }
+
+        let value = match cloned_game.get_outcome().unwrap() {
+            GameOutcome::Won(winner) if winner == player => 1.0,
+            GameOutcome::Won(_) => 0.0,
+            GameOutcome::Tie => config.draw_value,
+        };
+
+        Evaluation { priors, value }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs, SeedableRng};
+    use tic_tac_toe::game;
+
+    #[test]
+    fn test_rollout_evaluator_uniform_priors() {
+        let mut game = game::Game::new();
+        game.play(1, 1).unwrap();
+
+        let config = SearchConfig::default();
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let evaluation = RolloutEvaluator.evaluate(&game, &config, &mut rng);
+
+        assert_eq!(evaluation.priors.len(), 8);
+        for &(_, prior) in evaluation.priors.iter() {
+            assert!((prior - 0.125).abs() < 1e-7);
+        }
+        assert!([0.0, 0.5, 1.0].contains(&evaluation.value));
+    }
+
+    #[test]
+    fn test_rollout_evaluator_value_for_player_to_move() {
+        // O to move with (2, 1) and (2, 2) left, and X wins with whichever one O leaves
+        let mut game = game::Game::new();
+        for (row_index, col_index) in [(0, 0), (0, 2), (0, 1), (1, 0), (1, 1), (2, 0), (1, 2)] {
+            game.play(row_index, col_index).unwrap();
+        }
+
+        let config = SearchConfig::default();
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+
+        for _ in 0..10 {
+            let evaluation = RolloutEvaluator.evaluate(&game, &config, &mut rng);
+            assert!((evaluation.value - 0.0).abs() < 1e-7);
+        }
+    }
+}
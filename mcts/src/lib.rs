@@ -1 +1,12 @@
+pub mod evaluator;
 pub mod mcts_core;
+pub mod mcts_game;
+pub mod policy_value_net;
+pub mod rollout_policy;
+pub mod search_config;
+pub mod search_report;
+pub mod self_play;
+pub mod shared_tree;
+pub mod transposition_tree;
+pub mod tree_export;
+pub mod tree_policy;
@@ -1,162 +1,666 @@
 //! Contains functionality for core MCTN (Monte Carlo Tree Search)
-
-use rand::Rng;
-use std::cell::RefCell;
-use std::rc;
-use tic_tac_toe::game;
+//!
+//! The search tree is stored as a flat arena of nodes inside an `MCTree`. Nodes refer to their parent
+//! and children by index into the arena, so no reference counting or runtime borrow checks are needed.
+//!
+//! ## Examples
+//!
+//! ```
+//! use mcts::{mcts_core, search_config};
+//! use tic_tac_toe::game;
+//!
+//! let config = search_config::SearchConfig {
+//!     budget: search_config::SearchBudget::Iterations(100),
+//!     ..Default::default()
+//! };
+//!
+//! let mut tree = mcts_core::MCTree::new(&game::Game::new());
+//! let best_move = tree.think_about_best_move(&config);
+//! assert!(best_move.is_some());
+//! ```
+
+use crate::mcts_game::{GameOutcome, MCTSGame};
+use crate::search_config::{
+    ExpansionMode, FinalMovePolicy, Parallelism, SearchBudget, SearchConfig, SearchConfigError,
+};
+use crate::search_report::{MoveStats, SearchReport};
+use crate::tree_policy::{ChildStats, Puct, TreePolicy, Uct};
+use rand::{rngs, Rng, SeedableRng};
+use std::thread;
+use std::time;
+
+/// Index of a node inside the arena of an `MCTree`
+pub type NodeIndex = usize;
+
+/// Index of the root node inside the arena of an `MCTree`
+pub const ROOT: NodeIndex = 0;
+
+/// Moves played in a playout, each paired with the player who made it
+pub(crate) type PlayoutMoves<G> = Vec<(<G as MCTSGame>::Player, <G as MCTSGame>::Move)>;
+
+/// Root children, each given as (move, wins, visits)
+type RootChildren<G> = Vec<(<G as MCTSGame>::Move, f64, f64)>;
+
+/// Proven outcomes of root children, if known
+type RootProofs<G> = Vec<Option<GameOutcome<<G as MCTSGame>::Player>>>;
 
 /// Represents a node in the Monte Carlo tree. Includes
 /// - game state
 /// - parent which is the game state we reached current game state from
 /// - children which are the possible game states reachable from current state
 /// - game move played in parent state to reach current node (None if parent is None)
+/// - whether the player who made the move leading to current node is also the player to move in it, as when a
+///   player moves several times in a row. Always false for the root of a new tree
 /// - number of wins
+/// - sum of squared rewards, giving the variance of rewards together with the number of wins
 /// - number of visits
-pub struct MCTN {
-    game: game::Game,
-    parent: Option<rc::Weak<RefCell<MCTN>>>,
-    children: Vec<rc::Rc<RefCell<MCTN>>>,
-    move_from_parent: Option<(usize, usize)>,
+/// - number of AMAF (All-Moves-As-First) wins and visits, i.e. playouts through the parent in which the move
+///   from parent was played later by the same player
+/// - outcome the game is proven to reach from current state under perfect play, if known (MCTS-Solver).
+///   Terminal nodes are proven as soon as they are created
+/// - moves without a child yet, when children are added one at a time (None until the node is first expanded
+///   that way)
+/// - prior probability of the move from parent, given by an evaluator
+#[derive(Clone)]
+pub struct MCTN<G: MCTSGame> {
+    game: G,
+    parent: Option<NodeIndex>,
+    children: Vec<NodeIndex>,
+    move_from_parent: Option<G::Move>,
+    repeats_turn: bool,
     wins: f64,
+    squared_rewards: f64,
     visits: f64,
+    amaf_wins: f64,
+    amaf_visits: f64,
+    proven: Option<GameOutcome<G::Player>>,
+    untried_moves: Option<Vec<G::Move>>,
+    prior: f64,
 }
 
-impl MCTN {
-    /// Returns a newly created MCTN (Monte Carlo Tree Node) starting from `game_state` under a shared pointer
-    pub fn new(game_state: &game::Game) -> rc::Rc<RefCell<MCTN>> {
-        rc::Rc::new(RefCell::new(MCTN {
-            game: game_state.clone(),
-            parent: None,
-            move_from_parent: None,
-            children: Vec::new(),
-            wins: 0.0,
-            visits: 0.0,
-        }))
+impl<G: MCTSGame> MCTN<G> {
+    /// Gets the game state of the node
+    pub fn get_game(&self) -> &G {
+        &self.game
     }
 
-    /// Compute UCT (Upper Confidence Bound for Trees) score
-    fn uct(parent_visits: f64, child_wins: f64, child_visits: f64) -> f64 {
-        let win_rate = child_wins / child_visits;
+    /// Gets the index of the parent of the node, or None for the root
+    pub fn get_parent(&self) -> Option<NodeIndex> {
+        self.parent
+    }
 
-        // TODO: think about the choice of the constant C for exploration/exploitation trade-off
-        win_rate + (2.0 as f64).sqrt() * (parent_visits.ln() / child_visits).sqrt()
+    /// Gets the indices of the children of the node
+    pub fn get_children(&self) -> &[NodeIndex] {
+        &self.children
     }
 
-    /// Navigate from the current node until a leaf node is reaced based on UCT (Upper Confidence Bound for Trees) policy
-    fn select_node(node: rc::Rc<RefCell<MCTN>>) -> rc::Rc<RefCell<MCTN>> {
-        let mut max_uct_child: Option<rc::Rc<RefCell<MCTN>>> = None;
-        let mut max_uct = 0.0;
+    /// Gets the move played in the parent to reach the node, or None for the root
+    pub fn get_move_from_parent(&self) -> Option<G::Move> {
+        self.move_from_parent
+    }
 
-        for child in (*node).borrow().children.iter() {
-            let uct = MCTN::uct(
-                (*node).borrow().visits,
-                (**child).borrow().wins,
-                (**child).borrow().visits,
-            );
+    /// Gets the sum of rewards of the node, for the player who made the move leading to it
+    pub fn get_wins(&self) -> f64 {
+        self.wins
+    }
+
+    /// Gets the number of visits of the node
+    pub fn get_visits(&self) -> f64 {
+        self.visits
+    }
+
+    /// Gets the outcome the node is proven to reach, if known
+    pub fn get_proven(&self) -> Option<GameOutcome<G::Player>> {
+        self.proven
+    }
+}
+
+/// Represents the possible reasons when failing to advance the root of an `MCTree`
+#[derive(Debug, PartialEq, Eq)]
+pub enum AdvanceRootError {
+    IllegalMove,
+}
+
+/// Represents a Monte Carlo search tree. All nodes are owned by a single arena, with the root node
+/// stored first
+#[derive(Clone)]
+pub struct MCTree<G: MCTSGame> {
+    nodes: Vec<MCTN<G>>,
+}
+
+impl<G: MCTSGame> MCTree<G> {
+    /// Returns a newly created search tree whose root MCTN (Monte Carlo Tree Node) holds `game_state`
+    pub fn new(game_state: &G) -> Self {
+        MCTree {
+            nodes: vec![MCTN {
+                game: game_state.clone(),
+                parent: None,
+                move_from_parent: None,
+                repeats_turn: false,
+                children: Vec::new(),
+                wins: 0.0,
+                squared_rewards: 0.0,
+                visits: 0.0,
+                amaf_wins: 0.0,
+                amaf_visits: 0.0,
+                proven: game_state.get_outcome(),
+                untried_moves: None,
+                prior: 1.0,
+            }],
+        }
+    }
+
+    /// Gets the number of nodes in the tree
+    pub fn get_node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Gets the node at index `node`, where the root is at index `ROOT`. Panics if there is no such node
+    pub fn get_node(&self, node: NodeIndex) -> &MCTN<G> {
+        &self.nodes[node]
+    }
+
+    /// Gets the number of visits (i.e. backpropagated playouts) of the root node
+    pub fn get_root_visits(&self) -> f64 {
+        self.nodes[ROOT].visits
+    }
+
+    /// Gets the move and number of visits of every child of the root node
+    pub fn get_root_child_visits(&self) -> Vec<(G::Move, f64)> {
+        self.nodes[ROOT]
+            .children
+            .iter()
+            .map(|&child| {
+                let node = &self.nodes[child];
+                (node.move_from_parent.unwrap(), node.visits)
+            })
+            .collect()
+    }
+
+    /// Moves the root of the tree along `played_moves`, the moves played since the tree was searched.
+    /// The subtree under the resulting position becomes the new tree, keeping its accumulated statistics,
+    /// and the rest of the tree is dropped. If the position was never expanded, the tree restarts from it.
+    /// Returns an `Err` (leaving the tree untouched) if any of the moves is illegal
+    pub fn advance_root(&mut self, played_moves: &[G::Move]) -> Result<(), AdvanceRootError> {
+        let mut node = ROOT;
+        let mut unexplored_game: Option<G> = None;
+
+        for &game_move in played_moves {
+            if let Some(game) = &mut unexplored_game {
+                if !game.get_possible_plays().contains(&game_move) {
+                    return Err(AdvanceRootError::IllegalMove);
+                }
+
+                game.play_move(game_move);
+                continue;
+            }
+
+            let child = self.nodes[node]
+                .children
+                .iter()
+                .copied()
+                .find(|&child| self.nodes[child].move_from_parent == Some(game_move));
+
+            match child {
+                Some(child) => node = child,
+                None => {
+                    let game = &self.nodes[node].game;
+                    if !game.get_possible_plays().contains(&game_move) {
+                        return Err(AdvanceRootError::IllegalMove);
+                    }
+
+                    unexplored_game = Some(game.get_played_move(game_move));
+                }
+            }
+        }
+
+        match unexplored_game {
+            Some(game) => *self = MCTree::new(&game),
+            None => self.detach_subtree(node),
+        }
+
+        Ok(())
+    }
+
+    /// Keeps only the subtree under `node`, re-indexing the arena so that `node` becomes the root
+    fn detach_subtree(&mut self, node: NodeIndex) {
+        if node == ROOT {
+            return;
+        }
+
+        let mut old_nodes: Vec<Option<MCTN<G>>> = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .map(Some)
+            .collect();
+
+        // Breadth-first walk so that every parent is re-indexed before its children
+        let mut pending = std::collections::VecDeque::from([(node, None)]);
 
-            if uct > max_uct {
-                max_uct = uct;
-                max_uct_child = Some(rc::Rc::clone(child));
+        while let Some((old_index, new_parent)) = pending.pop_front() {
+            let mut moved_node = old_nodes[old_index].take().unwrap();
+            let new_index = self.nodes.len();
+
+            for &old_child in moved_node.children.iter() {
+                pending.push_back((old_child, Some(new_index)));
+            }
+
+            moved_node.parent = new_parent;
+            moved_node.children.clear();
+            if moved_node.parent.is_none() {
+                moved_node.move_from_parent = None;
+            }
+
+            if let Some(new_parent) = new_parent {
+                self.nodes[new_parent].children.push(new_index);
+            }
+
+            self.nodes.push(moved_node);
+        }
+    }
+
+    /// Estimates the win rate of `node`. With RAVE (Rapid Action Value Estimation) enabled, the win rate is blended
+    /// with the AMAF win rate, with a weight that starts at 1 and decays as the node collects visits:
+    /// beta = sqrt(k / (3 * visits + k)), where k is the RAVE equivalence parameter
+    fn node_value(&self, node: NodeIndex, config: &SearchConfig<G>) -> f64 {
+        let node = &self.nodes[node];
+        let win_rate = node.wins / node.visits;
+
+        match config.rave_equivalence {
+            Some(equivalence) if node.amaf_visits > 0.0 => {
+                let beta = (equivalence / (3.0 * node.visits + equivalence)).sqrt();
+                (1.0 - beta) * win_rate + beta * node.amaf_wins / node.amaf_visits
             }
+            _ => win_rate,
+        }
+    }
+
+    /// Converts `win_rate` of `node`, counted for the player who made the move leading to it, into the value of
+    /// `node` for the player to move in it
+    fn get_value_to_move(&self, node: NodeIndex, win_rate: f64) -> f64 {
+        if self.nodes[node].repeats_turn {
+            win_rate
+        } else {
+            1.0 - win_rate
+        }
+    }
+
+    /// Returns a boolean indicating whether `node` is proven to be lost by `player`
+    fn is_proven_loss(&self, node: NodeIndex, player: G::Player) -> bool {
+        matches!(self.nodes[node].proven, Some(GameOutcome::Won(winner)) if winner != player)
+    }
+
+    /// Returns a boolean indicating whether every move of `node` has a child. Always false for a terminal node
+    /// that was never expanded
+    fn is_fully_expanded(&self, node: NodeIndex) -> bool {
+        match &self.nodes[node].untried_moves {
+            Some(untried_moves) => untried_moves.is_empty(),
+            None => !self.nodes[node].children.is_empty(),
+        }
+    }
+
+    /// Gets the tree policy in `config`, which defaults to PUCT with an evaluator and to UCT without one
+    pub(crate) fn get_tree_policy(config: &SearchConfig<G>) -> &dyn TreePolicy {
+        match (&config.tree_policy, &config.evaluator) {
+            (Some(tree_policy), _) => tree_policy.as_ref(),
+            (None, Some(_)) => &Puct,
+            (None, None) => &Uct,
         }
+    }
+
+    /// Navigate from `node` until a leaf node is reaced based on the tree policy in `config`, which defaults to
+    /// PUCT (Predictor + Upper Confidence bounds applied to Trees) with an evaluator and to UCT (Upper Confidence
+    /// Bound for Trees) without one. Nodes with untried moves count as leaves. With the solver enabled, proven nodes
+    /// count as leaves too, and children proven to be lost by the player to move are never selected
+    fn select_node<R: Rng + ?Sized>(
+        &self,
+        node: NodeIndex,
+        config: &SearchConfig<G>,
+        rng: &mut R,
+    ) -> NodeIndex {
+        let tree_policy = Self::get_tree_policy(config);
+        let mut rng = rng;
+        let mut node = node;
+
+        loop {
+            if config.solver && self.nodes[node].proven.is_some() {
+                return node;
+            }
+
+            if !self.is_fully_expanded(node) {
+                return node;
+            }
+
+            let player = self.nodes[node].game.get_turn();
+            let parent_visits = self.nodes[node].visits;
+            let mut max_score_child: Option<(NodeIndex, f64)> = None;
+
+            // Unvisited children start from the value of the node for the player to move
+            let first_play_value = if parent_visits > 0.0 {
+                self.get_value_to_move(node, self.nodes[node].wins / parent_visits)
+            } else {
+                config.draw_value
+            };
+
+            for &child in self.nodes[node].children.iter() {
+                if config.solver && self.is_proven_loss(child, player) {
+                    continue;
+                }
+
+                let child_stats = ChildStats {
+                    value: if self.nodes[child].visits > 0.0 {
+                        self.node_value(child, config)
+                    } else {
+                        first_play_value
+                    },
+                    wins: self.nodes[child].wins,
+                    squared_rewards: self.nodes[child].squared_rewards,
+                    visits: self.nodes[child].visits,
+                    prior: self.nodes[child].prior,
+                };
+                let score = tree_policy.score(
+                    parent_visits,
+                    &child_stats,
+                    config.exploration_constant,
+                    &mut rng,
+                );
+
+                if max_score_child.is_none_or(|(_, max_score)| score > max_score) {
+                    max_score_child = Some((child, score));
+                }
+            }
 
-        match max_uct_child {
-            Some(child) => MCTN::select_node(child),
-            None => node,
+            match max_score_child {
+                Some((child, _)) => node = child,
+                None => return node,
+            }
         }
     }
 
-    /// Starting from the parent game, plays a move (`play_row_index`, `play_col_index`), and adds
-    /// the new game state as a child
-    fn play(parent: rc::Rc<RefCell<MCTN>>, play_row_index: usize, play_col_index: usize) {
-        let child = rc::Rc::new(RefCell::new(MCTN {
-            game: (*parent)
-                .borrow()
-                .game
-                .get_played(play_row_index, play_col_index)
-                .unwrap(),
+    /// Starting from the game in `parent`, plays `game_move`, and adds the new game state as a child.
+    /// Returns the index of the new child
+    fn play(&mut self, parent: NodeIndex, game_move: G::Move) -> NodeIndex {
+        let child = self.nodes.len();
+        let game = self.nodes[parent].game.get_played_move(game_move);
+        let proven = game.get_outcome();
+        let repeats_turn = game.get_turn() == self.nodes[parent].game.get_turn();
+
+        self.nodes.push(MCTN {
+            game,
             wins: 0.0,
+            squared_rewards: 0.0,
             visits: 0.0,
+            amaf_wins: 0.0,
+            amaf_visits: 0.0,
+            proven,
+            untried_moves: None,
+            prior: 0.0,
             children: Vec::new(),
-            move_from_parent: Some((play_row_index, play_col_index)),
-            parent: Some(rc::Rc::downgrade(&parent)),
-        }));
+            move_from_parent: Some(game_move),
+            repeats_turn,
+            parent: Some(parent),
+        });
+
+        self.nodes[parent].children.push(child);
+        child
+    }
 
-        (*parent).borrow_mut().children.push(child);
+    /// Gets the moves of `node` that children are added for. With symmetries merged, only one move is kept for
+    /// every group of moves leading to symmetric positions
+    fn get_expandable_moves(&self, node: NodeIndex, config: &SearchConfig<G>) -> Vec<G::Move> {
+        if config.merge_symmetries {
+            self.nodes[node].game.get_distinct_plays()
+        } else {
+            self.nodes[node].game.get_possible_plays()
+        }
     }
 
-    /// Starting from current MCTN, adds children corresponding to all possible next moves
+    /// Starting from `node`, adds children corresponding to all possible next moves that have no child yet
     /// If game is already over, it is a no-op
-    fn expand_node(node: rc::Rc<RefCell<MCTN>>) {
-        if (*node).borrow().children.len() > 0 {
-            panic!("Cannot expand a non-leaf node!");
+    fn expand_node(&mut self, node: NodeIndex, config: &SearchConfig<G>) {
+        let possible_plays = match self.nodes[node].untried_moves.take() {
+            Some(untried_moves) => {
+                self.nodes[node].untried_moves = Some(Vec::new());
+                untried_moves
+            }
+            None => {
+                if !self.nodes[node].children.is_empty() {
+                    panic!("Cannot expand a non-leaf node!");
+                }
+
+                self.get_expandable_moves(node, config)
+            }
+        };
+
+        for game_move in possible_plays {
+            self.play(node, game_move);
+        }
+    }
+
+    /// Starting from `node`, adds children corresponding to all possible next moves that have no child yet, then
+    /// gives every child its prior probability from `priors`. Moves missing from `priors` get a prior of 0
+    fn expand_node_with_priors(
+        &mut self,
+        node: NodeIndex,
+        priors: &[(G::Move, f64)],
+        config: &SearchConfig<G>,
+    ) {
+        self.expand_node(node, config);
+
+        for child_index in 0..self.nodes[node].children.len() {
+            let child = self.nodes[node].children[child_index];
+            let child_move = self.nodes[child].move_from_parent.unwrap();
+
+            self.nodes[child].prior = priors
+                .iter()
+                .find(|&&(game_move, _)| game_move == child_move)
+                .map_or(0.0, |&(_, prior)| prior);
         }
+    }
 
-        let possible_plays = (*node).borrow().game.get_possible_plays();
+    /// Starting from `node`, adds a single child for one of the moves without a child yet, picked at random.
+    /// Returns the index of the new child, or None if every move already has a child
+    fn expand_single_child<R: Rng + ?Sized>(
+        &mut self,
+        node: NodeIndex,
+        config: &SearchConfig<G>,
+        rng: &mut R,
+    ) -> Option<NodeIndex> {
+        if self.nodes[node].untried_moves.is_none() {
+            let untried_moves = if self.nodes[node].children.is_empty() {
+                self.get_expandable_moves(node, config)
+            } else {
+                Vec::new()
+            };
+            self.nodes[node].untried_moves = Some(untried_moves);
+        }
 
-        for (play_row_index, play_col_index) in possible_plays {
-            MCTN::play(rc::Rc::clone(&node), play_row_index, play_col_index);
+        let untried_moves = self.nodes[node].untried_moves.as_mut().unwrap();
+        if untried_moves.is_empty() {
+            return None;
         }
+
+        let game_move = untried_moves.swap_remove(rng.gen_range(0..untried_moves.len()));
+        Some(self.play(node, game_move))
     }
 
-    /// Simulate a random play starting from game state in `node` until game is over
-    fn simulate_playout(node: rc::Rc<RefCell<MCTN>>) -> game::GameState {
-        let mut cloned_game = (*node).borrow().game.clone();
-        let mut rng = rand::thread_rng();
+    /// Simulate a play starting from `game` until game is over, with moves picked by the rollout policy in
+    /// `config`. Returns the outcome, together with the moves of the playout and the players who made them
+    pub(crate) fn simulate_playout<R: Rng + ?Sized>(
+        game: &G,
+        config: &SearchConfig<G>,
+        rng: &mut R,
+    ) -> (GameOutcome<G::Player>, PlayoutMoves<G>) {
+        let mut cloned_game = game.clone();
+        let mut playout_moves = Vec::new();
+        let mut rng = rng;
 
         while !cloned_game.is_over() {
             let possible_plays = cloned_game.get_possible_plays();
-            let (rnd_row_idx, rnd_col_idx) = possible_plays[rng.gen_range(0..possible_plays.len())];
-            cloned_game.play(rnd_row_idx, rnd_col_idx).unwrap();
+            let rollout_move =
+                config
+                    .rollout_policy
+                    .choose_move(&cloned_game, &possible_plays, &mut rng);
+            playout_moves.push((cloned_game.get_turn(), rollout_move));
+            cloned_game.play_move(rollout_move);
         }
 
         assert_eq!(cloned_game.get_possible_plays().len(), 0);
-        assert_ne!(cloned_game.get_state(), game::GameState::Ongoing);
 
-        cloned_game.get_state()
+        (cloned_game.get_outcome().unwrap(), playout_moves)
     }
 
-    /// Starting form leaf node, refresh the state of wins/vists up the tree until root node is reached
-    fn backpropagate(node: rc::Rc<RefCell<MCTN>>, game_result: game::GameState) {
-        let node_player = (*node).borrow().game.get_turn();
-
+    /// Returns the reward of `game_result` for the player who made the move leading to a node where it is
+    /// `node_player`'s turn. That player is `node_player` themselves if `repeats_turn`, and their opponent otherwise
+    pub(crate) fn reward(
+        game_result: GameOutcome<G::Player>,
+        node_player: G::Player,
+        repeats_turn: bool,
+        config: &SearchConfig<G>,
+    ) -> f64 {
         match game_result {
-            game::GameState::XWon => match node_player {
-                game::GameTurn::TurnX => {
-                    (*node).borrow_mut().visits += 1.0;
-                }
-                game::GameTurn::TurnO => {
-                    (*node).borrow_mut().visits += 1.0;
-                    (*node).borrow_mut().wins += 1.0;
-                }
-            },
-            game::GameState::OWon => match node_player {
-                game::GameTurn::TurnX => {
-                    (*node).borrow_mut().visits += 1.0;
-                    (*node).borrow_mut().wins += 1.0;
-                }
-                game::GameTurn::TurnO => {
-                    (*node).borrow_mut().visits += 1.0;
+            GameOutcome::Won(winner) if (winner == node_player) != repeats_turn => 0.0,
+            GameOutcome::Won(_) => 1.0,
+            GameOutcome::Tie => config.draw_value,
+        }
+    }
+
+    /// Tries to prove the outcome of `node` from its children: the player to move wins if any child is a proven win
+    /// for them, and otherwise gets the best outcome among its children once all of them are proven.
+    /// Returns a boolean indicating whether `node` is proven
+    fn update_proof(&mut self, node: NodeIndex) -> bool {
+        if self.nodes[node].proven.is_some() {
+            return true;
+        }
+
+        if self.nodes[node].children.is_empty() {
+            return false;
+        }
+
+        let player = self.nodes[node].game.get_turn();
+        let mut all_proven = self.is_fully_expanded(node);
+        let mut best_outcome: Option<GameOutcome<G::Player>> = None;
+
+        for &child in self.nodes[node].children.iter() {
+            match self.nodes[child].proven {
+                Some(GameOutcome::Won(winner)) if winner == player => {
+                    best_outcome = Some(GameOutcome::Won(winner));
+                    all_proven = true;
+                    break;
                 }
-            },
-            game::GameState::Tie => match node_player {
-                game::GameTurn::TurnX => {
-                    (*node).borrow_mut().visits += 1.0;
-                    (*node).borrow_mut().wins += 0.5;
+                Some(GameOutcome::Tie) => best_outcome = Some(GameOutcome::Tie),
+                Some(outcome) => {
+                    if best_outcome.is_none() {
+                        best_outcome = Some(outcome);
+                    }
                 }
-                game::GameTurn::TurnO => {
-                    (*node).borrow_mut().visits += 1.0;
-                    (*node).borrow_mut().wins += 0.5;
+                None => all_proven = false,
+            }
+        }
+
+        if all_proven {
+            self.nodes[node].proven = best_outcome;
+        }
+
+        self.nodes[node].proven.is_some()
+    }
+
+    /// Starting form leaf node, refresh the state of wins/vists up the tree until root node is reached.
+    /// Wins are counted from the perspective of the player who made the move leading to each node.
+    /// With RAVE enabled, every node on the way also credits the AMAF statistics of those of its children whose
+    /// move was played later on by the same player, either in the tree below it or in `playout_moves`.
+    /// With the solver enabled, proofs are carried up the tree for as long as nodes on the way get proven
+    fn backpropagate(
+        &mut self,
+        node: NodeIndex,
+        game_result: GameOutcome<G::Player>,
+        config: &SearchConfig<G>,
+        playout_moves: &[(G::Player, G::Move)],
+    ) {
+        let reward = |node_player, repeats_turn| {
+            Self::reward(game_result, node_player, repeats_turn, config)
+        };
+        self.backpropagate_reward(node, reward, config, playout_moves);
+    }
+
+    /// Same as `backpropagate`, with an estimated `value` of the game in `node` for the player to move there
+    /// instead of a game result
+    fn backpropagate_value(&mut self, node: NodeIndex, value: f64, config: &SearchConfig<G>) {
+        let player = self.nodes[node].game.get_turn();
+        let reward = |node_player, repeats_turn| {
+            if (node_player == player) != repeats_turn {
+                1.0 - value
+            } else {
+                value
+            }
+        };
+        self.backpropagate_reward(node, reward, config, &[]);
+    }
+
+    /// Backpropagates from `node` like `backpropagate`, where `reward` gives the reward of the player who made the
+    /// move leading to a node where it is the turn of a given player, given whether that move repeated the turn
+    fn backpropagate_reward<F: Fn(G::Player, bool) -> f64>(
+        &mut self,
+        node: NodeIndex,
+        reward: F,
+        config: &SearchConfig<G>,
+        playout_moves: &[(G::Player, G::Move)],
+    ) {
+        let rave_enabled = config.rave_equivalence.is_some();
+        let mut subsequent_moves = if rave_enabled {
+            playout_moves.to_vec()
+        } else {
+            Vec::new()
+        };
+        let mut proving = config.solver;
+        let mut current = Some(node);
+
+        while let Some(node) = current {
+            let node_reward = reward(
+                self.nodes[node].game.get_turn(),
+                self.nodes[node].repeats_turn,
+            );
+            self.nodes[node].visits += 1.0;
+            self.nodes[node].wins += node_reward;
+            self.nodes[node].squared_rewards += node_reward * node_reward;
+
+            if proving {
+                proving = self.update_proof(node);
+            }
+
+            current = self.nodes[node].parent;
+
+            if rave_enabled {
+                self.update_amaf(node, &reward, &subsequent_moves);
+
+                if let Some(parent) = current {
+                    let node_move = self.nodes[node].move_from_parent.unwrap();
+                    subsequent_moves.push((self.nodes[parent].game.get_turn(), node_move));
                 }
-            },
-            _ => panic!("Cannot back propagate result other than XWon, OWon, Tie"),
+            }
         }
+    }
 
-        match &(*node).borrow().parent {
-            Some(parent) => {
-                MCTN::backpropagate(rc::Rc::clone(&parent.upgrade().unwrap()), game_result)
+    /// Credits `reward` to the AMAF statistics of every child of `node` whose move appears in `subsequent_moves`
+    /// as played by the player to move at `node`
+    fn update_amaf<F: Fn(G::Player, bool) -> f64>(
+        &mut self,
+        node: NodeIndex,
+        reward: &F,
+        subsequent_moves: &[(G::Player, G::Move)],
+    ) {
+        let node_player = self.nodes[node].game.get_turn();
+
+        for child_index in 0..self.nodes[node].children.len() {
+            let child = self.nodes[node].children[child_index];
+            let child_move = self.nodes[child].move_from_parent.unwrap();
+
+            if subsequent_moves.contains(&(node_player, child_move)) {
+                self.nodes[child].amaf_visits += 1.0;
+                self.nodes[child].amaf_wins += reward(
+                    self.nodes[child].game.get_turn(),
+                    self.nodes[child].repeats_turn,
+                );
             }
-            None => {}
         }
     }
 
@@ -165,77 +669,595 @@ impl MCTN {
     /// 2- expanding leaf node to include its children of possible new moves
     /// 3- simulating a random playout starting from each of the children
     /// 4- backpropagating game results of random playouts from each new children up to the root node
-    fn mcts_update(root: rc::Rc<RefCell<MCTN>>) {
-        let leaf = MCTN::select_node(rc::Rc::clone(&root));
-        MCTN::expand_node(rc::Rc::clone(&leaf));
+    ///
+    /// When expanding a single child at a time, only one child is added once the leaf has enough visits, and a
+    /// single playout is run from it (or from the leaf itself, if no child was added).
+    /// With an evaluator, all children of the leaf are added with the priors of the evaluator, and the value of the
+    /// leaf is backpropagated instead of playout results. A leaf where the game is over backpropagates its outcome.
+    /// With the solver enabled, a proven leaf is neither expanded nor played out, and its proven outcome is
+    /// backpropagated instead
+    fn mcts_update<R: Rng + ?Sized>(&mut self, config: &SearchConfig<G>, rng: &mut R) {
+        let leaf = self.select_node(ROOT, config, rng);
+
+        if config.solver {
+            if let Some(proven_outcome) = self.nodes[leaf].proven {
+                self.backpropagate(leaf, proven_outcome, config, &[]);
+                return;
+            }
+        }
+
+        if let Some(evaluator) = &config.evaluator {
+            match self.nodes[leaf].game.get_outcome() {
+                Some(game_result) => self.backpropagate(leaf, game_result, config, &[]),
+                None => {
+                    let mut rng = rng;
+                    let evaluation = evaluator.evaluate(&self.nodes[leaf].game, config, &mut rng);
+                    self.expand_node_with_priors(leaf, &evaluation.priors, config);
+                    self.backpropagate_value(leaf, evaluation.value, config);
+                }
+            }
+            return;
+        }
+
+        match config.expansion {
+            ExpansionMode::All => {
+                // A leaf ending the game has no children to expand, so its outcome is backpropagated instead
+                if let Some(game_result) = self.nodes[leaf].game.get_outcome() {
+                    self.backpropagate(leaf, game_result, config, &[]);
+                    return;
+                }
+
+                let first_new_child = self.nodes[leaf].children.len();
+                self.expand_node(leaf, config);
+
+                for child_index in first_new_child..self.nodes[leaf].children.len() {
+                    let child = self.nodes[leaf].children[child_index];
+                    let (game_result, playout_moves) =
+                        Self::simulate_playout(&self.nodes[child].game, config, rng);
+                    self.backpropagate(child, game_result, config, &playout_moves);
+                }
+            }
+            ExpansionMode::Single { threshold } => {
+                let playout_start = if self.nodes[leaf].visits >= threshold as f64 {
+                    self.expand_single_child(leaf, config, rng).unwrap_or(leaf)
+                } else {
+                    leaf
+                };
+
+                let (game_result, playout_moves) =
+                    Self::simulate_playout(&self.nodes[playout_start].game, config, rng);
+                self.backpropagate(playout_start, game_result, config, &playout_moves);
+            }
+        }
+    }
+
+    /// Scores a root child with (`child_wins`, `child_visits`) for the score-based final move policies
+    fn final_move_score(
+        config: &SearchConfig<G>,
+        parent_visits: f64,
+        child_wins: f64,
+        child_visits: f64,
+    ) -> f64 {
+        if child_visits <= 0.0 {
+            return f64::NEG_INFINITY;
+        }
+
+        let win_rate = child_wins / child_visits;
+
+        match config.final_move_policy {
+            FinalMovePolicy::SecureChild => {
+                win_rate
+                    - config.exploration_constant
+                        * (parent_visits.ln().max(0.0) / child_visits).sqrt()
+            }
+            _ => win_rate,
+        }
+    }
 
-        let children: Vec<rc::Rc<RefCell<MCTN>>> = (*leaf).borrow().children.clone();
+    /// Picks the final move among root children given as (move, wins, visits), according to the final move
+    /// policy in `config`. Always returns a move when `children` is non-empty
+    pub(crate) fn choose_final_move(
+        children: &[(G::Move, f64, f64)],
+        parent_visits: f64,
+        config: &SearchConfig<G>,
+    ) -> Option<G::Move> {
+        let most_visited = |children: &[(G::Move, f64, f64)]| {
+            let mut best: Option<(G::Move, f64)> = None;
+            for &(game_move, _, visits) in children {
+                if best.is_none_or(|(_, max_visits)| visits > max_visits) {
+                    best = Some((game_move, visits));
+                }
+            }
+            best
+        };
+
+        let highest_score = |children: &[(G::Move, f64, f64)]| {
+            let mut best: Option<(G::Move, f64)> = None;
+            for &(game_move, wins, visits) in children {
+                let score = Self::final_move_score(config, parent_visits, wins, visits);
+                if best.is_none_or(|(_, max_score)| score > max_score) {
+                    best = Some((game_move, score));
+                }
+            }
+            best
+        };
 
-        for child in children {
-            let game_result = MCTN::simulate_playout(rc::Rc::clone(&child));
-            MCTN::backpropagate(rc::Rc::clone(&child), game_result);
+        match config.final_move_policy {
+            FinalMovePolicy::MaxChild | FinalMovePolicy::SecureChild => {
+                highest_score(children).map(|(game_move, _)| game_move)
+            }
+            FinalMovePolicy::RobustChild => most_visited(children).map(|(game_move, _)| game_move),
+            FinalMovePolicy::MaxRobustChild => {
+                let (robust_move, max_visits) = most_visited(children)?;
+                let (_, max_score) = highest_score(children)?;
+
+                // Prefer a child that is best on both counts, otherwise trust the visit counts
+                children
+                    .iter()
+                    .find(|&&(_, wins, visits)| {
+                        visits == max_visits
+                            && Self::final_move_score(config, parent_visits, wins, visits)
+                                == max_score
+                    })
+                    .map(|&(game_move, _, _)| game_move)
+                    .or(Some(robust_move))
+            }
         }
     }
 
+    /// Gets the proven outcome of the root child reached by `game_move`, if that child exists and is proven
+    fn get_root_child_proof(&self, game_move: G::Move) -> Option<GameOutcome<G::Player>> {
+        self.nodes[ROOT]
+            .children
+            .iter()
+            .find(|&&child| self.nodes[child].move_from_parent == Some(game_move))
+            .and_then(|&child| self.nodes[child].proven)
+    }
+
+    /// Narrows down root children given as (move, wins, visits) to the ones worth playing according to their
+    /// `proofs`: the proven wins of `player` if there are any, otherwise the children not proven to be losses,
+    /// unless all of them are
+    fn keep_unrefuted_moves(
+        children: Vec<(G::Move, f64, f64)>,
+        proofs: &[Option<GameOutcome<G::Player>>],
+        player: G::Player,
+    ) -> Vec<(G::Move, f64, f64)> {
+        let is_loss = |proof: Option<GameOutcome<G::Player>>| matches!(proof, Some(GameOutcome::Won(winner)) if winner != player);
+        let has_win = proofs.contains(&Some(GameOutcome::Won(player)));
+        let all_lost = proofs.iter().all(|&proof| is_loss(proof));
+
+        children
+            .into_iter()
+            .zip(proofs.iter())
+            .filter(|&(_, &proof)| {
+                if has_win {
+                    proof == Some(GameOutcome::Won(player))
+                } else {
+                    all_lost || !is_loss(proof)
+                }
+            })
+            .map(|(child, _)| child)
+            .collect()
+    }
+
+    /// Gets the root children as (move, wins, visits), together with their proven outcomes
+    fn get_root_children(&self) -> (RootChildren<G>, RootProofs<G>) {
+        self.nodes[ROOT]
+            .children
+            .iter()
+            .map(|&child| {
+                let node = &self.nodes[child];
+                (
+                    (node.move_from_parent.unwrap(), node.wins, node.visits),
+                    node.proven,
+                )
+            })
+            .unzip()
+    }
+
     /// This function is supposed to be called after the tree has been expanded and explored
-    /// After exploration, it selects the move to get from parent to child with highest win rate
-    fn select_best_move(root: rc::Rc<RefCell<MCTN>>) -> Option<(usize, usize)> {
-        let mut best_move: Option<(usize, usize)> = None;
-        let mut max_win_rate = 0.0;
-        for child in (*root).borrow().children.iter() {
-            let win_rate = (**child).borrow().wins / ((**child).borrow().visits);
+    /// After exploration, it selects the move to get from root to a child according to the final move policy.
+    /// With the solver enabled, proven wins are always played and proven losses avoided whenever possible
+    fn select_best_move(&self, config: &SearchConfig<G>) -> Option<G::Move> {
+        let (children, proofs) = self.get_root_children();
+        self.select_best_move_among(&children, &proofs, self.nodes[ROOT].visits, config)
+    }
+
+    /// Same as `select_best_move`, among `children` given as (move, wins, visits) with their `proofs`
+    fn select_best_move_among(
+        &self,
+        children: &[(G::Move, f64, f64)],
+        proofs: &[Option<GameOutcome<G::Player>>],
+        root_visits: f64,
+        config: &SearchConfig<G>,
+    ) -> Option<G::Move> {
+        let mut children = children.to_vec();
+
+        if config.solver {
+            children =
+                Self::keep_unrefuted_moves(children, proofs, self.nodes[ROOT].game.get_turn());
+        }
+
+        Self::choose_final_move(&children, root_visits, config)
+    }
+
+    /// Gets the statistics of root children given as (move, wins, visits) with their `proofs`, by decreasing
+    /// number of visits
+    fn get_move_stats(
+        children: &[(G::Move, f64, f64)],
+        proofs: &[Option<GameOutcome<G::Player>>],
+        root_visits: f64,
+        config: &SearchConfig<G>,
+    ) -> Vec<MoveStats<G>> {
+        let mut move_stats: Vec<MoveStats<G>> = children
+            .iter()
+            .zip(proofs.iter())
+            .map(|(&(game_move, wins, visits), &proven)| {
+                let (mean_value, ucb) = if visits > 0.0 {
+                    let mean_value = wins / visits;
+                    let exploration = (root_visits.ln().max(0.0) / visits).sqrt();
+                    (
+                        mean_value,
+                        mean_value + config.exploration_constant * exploration,
+                    )
+                } else {
+                    (0.0, f64::INFINITY)
+                };
+
+                MoveStats {
+                    game_move,
+                    visits,
+                    mean_value,
+                    ucb,
+                    proven,
+                }
+            })
+            .collect();
+
+        move_stats.sort_by(|a, b| b.visits.total_cmp(&a.visits));
+        move_stats
+    }
+
+    /// Gets the principal variation starting with `first_move`: the move, followed by the most visited child at
+    /// every level below the root child it leads to, until a node without visited children is reached
+    fn get_principal_variation(&self, first_move: G::Move) -> Vec<G::Move> {
+        let mut principal_variation = vec![first_move];
+        let mut current = self.nodes[ROOT]
+            .children
+            .iter()
+            .copied()
+            .find(|&child| self.nodes[child].move_from_parent == Some(first_move));
+
+        while let Some(node) = current {
+            current = None;
+            let mut max_visits = 0.0;
+
+            for &child in self.nodes[node].children.iter() {
+                if self.nodes[child].visits > max_visits {
+                    max_visits = self.nodes[child].visits;
+                    current = Some(child);
+                }
+            }
+
+            if let Some(child) = current {
+                principal_variation.push(self.nodes[child].move_from_parent.unwrap());
+            }
+        }
+
+        principal_variation
+    }
+
+    /// Gets the depth of the deepest node of the tree, the root being at depth 0
+    pub fn get_max_depth(&self) -> usize {
+        let mut max_depth = 0;
+        let mut stack = vec![(ROOT, 0)];
 
-            if win_rate > max_win_rate {
-                max_win_rate = win_rate;
-                best_move = Some((**child).borrow().move_from_parent.unwrap());
+        while let Some((node, depth)) = stack.pop() {
+            max_depth = max_depth.max(depth);
+
+            for &child in self.nodes[node].children.iter() {
+                stack.push((child, depth + 1));
             }
         }
 
-        best_move
+        max_depth
+    }
+
+    /// Builds the report of a search over `trees`, the first of which is `self`, that picked `best_move` from
+    /// their merged root `children` given as (move, wins, visits), with their `proofs`
+    #[allow(clippy::too_many_arguments)]
+    fn build_report(
+        &self,
+        trees: &[&MCTree<G>],
+        children: &[(G::Move, f64, f64)],
+        proofs: &[Option<GameOutcome<G::Player>>],
+        root_visits: f64,
+        best_move: Option<G::Move>,
+        config: &SearchConfig<G>,
+        iterations: u32,
+        start: time::Instant,
+    ) -> SearchReport<G> {
+        let root_wins: f64 = trees.iter().map(|tree| tree.nodes[ROOT].wins).sum();
+
+        SearchReport {
+            best_move,
+            root_moves: Self::get_move_stats(children, proofs, root_visits, config),
+            root_visits,
+            root_value: if root_visits > 0.0 {
+                self.get_value_to_move(ROOT, root_wins / root_visits)
+            } else {
+                0.0
+            },
+            principal_variation: best_move.map_or_else(Vec::new, |game_move| {
+                self.get_principal_variation(game_move)
+            }),
+            iterations,
+            node_count: trees.iter().map(|tree| tree.get_node_count()).sum(),
+            max_depth: trees
+                .iter()
+                .map(|tree| tree.get_max_depth())
+                .max()
+                .unwrap_or(0),
+            elapsed: start.elapsed(),
+        }
+    }
+
+    /// Returns a boolean indicating whether a search that started at `start` and ran `iterations` iterations
+    /// has used up the budget in `config` or hit one of its limits, given the `node_count` of its tree
+    pub(crate) fn is_budget_used(
+        config: &SearchConfig<G>,
+        start: time::Instant,
+        iterations: u32,
+        node_count: usize,
+    ) -> bool {
+        let budget_used = match config.budget {
+            SearchBudget::Iterations(max_iterations) => iterations >= max_iterations,
+            SearchBudget::Time(duration) => start.elapsed() >= duration,
+        };
+
+        budget_used || config.limits.is_hit(node_count)
     }
 
-    /// Performes `iterations` iterations of MCTS algorithm and responds with best move
-    pub fn think_about_best_move(
-        root: rc::Rc<RefCell<MCTN>>,
+    /// Returns a boolean indicating whether a search that started at `start` and ran `iterations` iterations
+    /// has used up its budget or hit one of its limits. With the solver enabled, the search is also over once the
+    /// root is proven
+    fn is_search_over(
+        &self,
+        config: &SearchConfig<G>,
+        start: time::Instant,
         iterations: u32,
-    ) -> Option<(usize, usize)> {
-        for _ in 0..iterations {
-            MCTN::mcts_update(rc::Rc::clone(&root));
+    ) -> bool {
+        let root_proven = config.solver && self.nodes[ROOT].proven.is_some();
+
+        root_proven || Self::is_budget_used(config, start, iterations, self.get_node_count())
+    }
+
+    /// Performes iterations of MCTS algorithm until the budget in `config` is used up, or one of its limits is hit.
+    /// Makes sure the root is expanded, so that a move can be picked even if no iteration ran.
+    /// Returns the number of iterations run
+    fn run_search<R: Rng + ?Sized>(&mut self, config: &SearchConfig<G>, rng: &mut R) -> u32 {
+        let start = time::Instant::now();
+        let mut iterations = 0;
+
+        while !self.is_search_over(config, start, iterations) {
+            self.mcts_update(config, rng);
+            iterations += 1;
+        }
+
+        if self.nodes[ROOT].children.is_empty() {
+            self.expand_node(ROOT, config);
+        }
+
+        iterations
+    }
+
+    /// Sums the (wins, visits) of root children with the same move across `trees`, which must share the same
+    /// root game. Returns the merged children as (move, wins, visits) together with the summed root visits
+    fn merge_root_stats(trees: &[&MCTree<G>]) -> (Vec<(G::Move, f64, f64)>, f64) {
+        let mut merged: Vec<(G::Move, f64, f64)> = Vec::new();
+        let mut root_visits = 0.0;
+
+        for tree in trees {
+            root_visits += tree.nodes[ROOT].visits;
+
+            for &child in tree.nodes[ROOT].children.iter() {
+                let node = &tree.nodes[child];
+                let game_move = node.move_from_parent.unwrap();
+
+                match merged
+                    .iter_mut()
+                    .find(|(merged_move, _, _)| *merged_move == game_move)
+                {
+                    Some((_, wins, visits)) => {
+                        *wins += node.wins;
+                        *visits += node.visits;
+                    }
+                    None => merged.push((game_move, node.wins, node.visits)),
+                }
+            }
+        }
+
+        (merged, root_visits)
+    }
+
+    /// Searches `threads` independent copies of the tree at once, each with its own random number generator
+    /// derived from `config.seed`, then picks the move from the merged root statistics. Only the statistics
+    /// of the first copy are kept in `self`
+    fn search_root_parallel(
+        &mut self,
+        config: &SearchConfig<G>,
+        threads: usize,
+    ) -> SearchReport<G> {
+        let start = time::Instant::now();
+        let base_seed = config.seed.unwrap_or_else(rand::random);
+        let mut helper_trees: Vec<MCTree<G>> = (1..threads).map(|_| self.clone()).collect();
+
+        let iterations = thread::scope(|scope| {
+            let handles: Vec<_> = helper_trees
+                .iter_mut()
+                .enumerate()
+                .map(|(thread_index, tree)| {
+                    let seed = base_seed.wrapping_add(thread_index as u64 + 1);
+                    scope.spawn(move || {
+                        tree.run_search(config, &mut rngs::StdRng::seed_from_u64(seed))
+                    })
+                })
+                .collect();
+
+            let own_iterations =
+                self.run_search(config, &mut rngs::StdRng::seed_from_u64(base_seed));
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .sum::<u32>()
+                + own_iterations
+        });
+
+        let trees: Vec<&MCTree<G>> = std::iter::once(&*self).chain(helper_trees.iter()).collect();
+        let (children, root_visits) = Self::merge_root_stats(&trees);
+
+        // A proof found by any of the trees holds for all of them
+        let proofs: RootProofs<G> = children
+            .iter()
+            .map(|&(game_move, _, _)| {
+                trees
+                    .iter()
+                    .find_map(|tree| tree.get_root_child_proof(game_move))
+            })
+            .collect();
+        let best_move = self.select_best_move_among(&children, &proofs, root_visits, config);
+
+        self.build_report(
+            &trees,
+            &children,
+            &proofs,
+            root_visits,
+            best_move,
+            config,
+            iterations,
+            start,
+        )
+    }
+
+    /// Checks that this driver supports every option in `config`. Returns an `Err` for tree parallelism, which
+    /// only `shared_tree::SharedTree` supports
+    pub fn check_config(config: &SearchConfig<G>) -> Result<(), SearchConfigError> {
+        match config.parallelism {
+            Parallelism::TreeParallel { .. } => Err(SearchConfigError::UnsupportedParallelism),
+            _ => Ok(()),
+        }
+    }
+
+    /// Performes iterations of MCTS algorithm until the budget in `config` is used up, or one of its limits is hit,
+    /// and reports on the search, including the best move found so far. A move is always picked unless the game is
+    /// already over. Playouts are driven by a random number generator seeded from `config.seed`. Runs on several
+    /// threads when `config.parallelism` asks for root parallelism. Panics in debug builds if `check_config`
+    /// rejects `config`, and searches sequentially in release builds
+    pub fn search(&mut self, config: &SearchConfig<G>) -> SearchReport<G> {
+        let supported = Self::check_config(config);
+        debug_assert!(
+            supported.is_ok(),
+            "Unsupported search config: {:?}",
+            supported
+        );
+
+        match config.parallelism {
+            Parallelism::RootParallel { threads } => {
+                self.search_root_parallel(config, threads.max(1))
+            }
+            _ => {
+                let mut rng = config.get_rng();
+                self.search_with_rng(config, &mut rng)
+            }
         }
+    }
+
+    /// Same as `search`, but playouts draw from the caller's `rng`, and `config.seed` and `config.parallelism` are
+    /// ignored. With an iteration budget, the same tree and the same `rng` state always lead to the same move
+    pub fn search_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        config: &SearchConfig<G>,
+        rng: &mut R,
+    ) -> SearchReport<G> {
+        let start = time::Instant::now();
+        let iterations = self.run_search(config, rng);
+        let (children, proofs) = self.get_root_children();
+        let best_move = self.select_best_move(config);
+
+        self.build_report(
+            &[self],
+            &children,
+            &proofs,
+            self.nodes[ROOT].visits,
+            best_move,
+            config,
+            iterations,
+            start,
+        )
+    }
+
+    /// Performes iterations of MCTS algorithm until the budget in `config` is used up, or one of its limits is hit,
+    /// and responds with best move found so far. Same as `search`, keeping only the move
+    pub fn think_about_best_move(&mut self, config: &SearchConfig<G>) -> Option<G::Move> {
+        self.search(config).best_move
+    }
 
-        MCTN::select_best_move(rc::Rc::clone(&root))
+    /// Same as `search_with_rng`, keeping only the move
+    pub fn think_about_best_move_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        config: &SearchConfig<G>,
+        rng: &mut R,
+    ) -> Option<G::Move> {
+        self.search_with_rng(config, rng).best_move
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
+    use crate::evaluator::{Evaluation, Evaluator, RolloutEvaluator};
+    use crate::mcts_game::WildGame;
+    use crate::search_config::SearchLimits;
+    use crate::tree_policy::{ThompsonSampling, Ucb1Tuned, UcbV};
+    use std::sync;
+    use tic_tac_toe::board::Cell::{O, X};
+    use tic_tac_toe::game;
+
+    fn iterations_config(iterations: u32) -> SearchConfig<game::Game> {
+        SearchConfig {
+            budget: SearchBudget::Iterations(iterations),
+            ..Default::default()
+        }
+    }
 
     #[test]
     fn test_expand_node() {
-        let root = MCTN::new(&game::Game::new());
-        MCTN::expand_node(rc::Rc::clone(&root));
+        let mut tree = MCTree::new(&game::Game::new());
+        tree.expand_node(ROOT, &SearchConfig::default());
 
         // Check number of children is correct (first move has 9 possible choices)
-        assert_eq!((*root).borrow().children.len(), 9);
+        assert_eq!(tree.nodes[ROOT].children.len(), 9);
 
-        for node in (*root).borrow().children.iter() {
+        for &node in tree.nodes[ROOT].children.iter() {
             // Assert move has been made
-            assert_eq!((**node).borrow().game.get_possible_plays().len(), 8);
+            assert_eq!(tree.nodes[node].game.get_possible_plays().len(), 8);
 
             // Assert game is not over (Tic-Tac-Toe cannot end in one move)
-            assert_eq!((**node).borrow().game.get_state(), game::GameState::Ongoing);
+            assert_eq!(tree.nodes[node].game.get_state(), game::GameState::Ongoing);
 
             // Assert turn has been switched
-            assert_eq!((**node).borrow().game.get_turn(), game::GameTurn::TurnO);
+            assert_eq!(tree.nodes[node].game.get_turn(), game::GameTurn::TurnO);
+
+            // Assert child links back to root
+            assert_eq!(tree.nodes[node].parent, Some(ROOT));
         }
 
         use std::collections;
-        let child_set: collections::HashSet<game::Game> = (*root)
-            .borrow()
+        let child_set: collections::HashSet<game::Game> = tree.nodes[ROOT]
             .children
             .iter()
-            .map(|node| (**node).borrow().game.clone())
+            .map(|&node| tree.nodes[node].game.clone())
             .collect();
 
         // Make sure the child game states (the boards in this case) are unique/different
@@ -244,135 +1266,1316 @@ mod tests {
 
     #[test]
     fn test_select_node() {
-        let root = MCTN::new(&game::Game::new());
+        let mut tree = MCTree::new(&game::Game::new());
 
         // X at (0, 0) added as a first possibility child
-        MCTN::play(rc::Rc::clone(&root), 0, 0);
+        tree.play(ROOT, (0, 0));
 
         // X at (2, 2) added as a second possibility child
-        MCTN::play(rc::Rc::clone(&root), 2, 2);
+        tree.play(ROOT, (2, 2));
 
         // At this point, we have a root node with two children at level 1
 
-        assert_eq!((*root).borrow().children.len(), 2);
+        assert_eq!(tree.nodes[ROOT].children.len(), 2);
 
         // Pick a child arbitrarily
-        let a_child = rc::Rc::clone((*root).borrow().children.iter().next().unwrap());
+        let a_child = tree.nodes[ROOT].children[0];
+        let other_child = tree.nodes[ROOT].children[1];
 
         // Backpropagate XWon from the chosen child. This should increase UCT score for that child
-        MCTN::backpropagate(rc::Rc::clone(&a_child), game::GameState::XWon);
+        tree.backpropagate(
+            a_child,
+            GameOutcome::Won(game::GameTurn::TurnX),
+            &SearchConfig::default(),
+            &[],
+        );
+
+        // Backpropagate OWon from the other child, since unvisited children are always selected first
+        tree.backpropagate(
+            other_child,
+            GameOutcome::Won(game::GameTurn::TurnO),
+            &SearchConfig::default(),
+            &[],
+        );
 
-        let selected_child = MCTN::select_node(rc::Rc::clone(&root));
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let selected_child = tree.select_node(ROOT, &SearchConfig::default(), &mut rng);
 
         // Make sure we select the child with high UCT
-        assert_eq!(
-            (*selected_child).borrow().game == (*a_child).borrow().game,
-            true
-        );
+        assert_eq!(selected_child, a_child);
     }
 
     #[test]
     fn test_backpropagate_xwon() {
         // Start with new game (empty board)
-        let root = MCTN::new(&game::Game::new());
+        let mut tree = MCTree::new(&game::Game::new());
 
         // X at (0, 0) added as a child
-        MCTN::play(rc::Rc::clone(&root), 0, 0);
+        let child = tree.play(ROOT, (0, 0));
 
-        assert_eq!((*root).borrow().children.len(), 1);
-        let child = rc::Rc::clone((*root).borrow().children.iter().next().unwrap());
+        assert_eq!(tree.nodes[ROOT].children.len(), 1);
 
         // Propagate state XWon up from child to parent
-        MCTN::backpropagate(rc::Rc::clone(&child), game::GameState::XWon);
+        tree.backpropagate(
+            child,
+            GameOutcome::Won(game::GameTurn::TurnX),
+            &SearchConfig::default(),
+            &[],
+        );
 
         // Make sure child increased both wins and vists
-        assert_eq!(((*child).borrow().wins - 1.0).abs() < 1e-7, true);
-        assert_eq!(((*child).borrow().visits - 1.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[child].wins - 1.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[child].visits - 1.0).abs() < 1e-7, true);
 
         // Make sure parent only increased vists
-        assert_eq!(((*root).borrow().wins - 0.0).abs() < 1e-7, true);
-        assert_eq!(((*root).borrow().visits - 1.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[ROOT].wins - 0.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[ROOT].visits - 1.0).abs() < 1e-7, true);
     }
 
     #[test]
     fn test_backpropagate_owon() {
         // Start with new game (empty board)
-        let root = MCTN::new(&game::Game::new());
+        let mut tree = MCTree::new(&game::Game::new());
 
         // X at (0, 0) added as a child
-        MCTN::play(rc::Rc::clone(&root), 0, 0);
+        let child = tree.play(ROOT, (0, 0));
 
-        assert_eq!((*root).borrow().children.len(), 1);
-        let child = rc::Rc::clone((*root).borrow().children.iter().next().unwrap());
+        assert_eq!(tree.nodes[ROOT].children.len(), 1);
 
         // Propagate state OWn up from child to parent
-        MCTN::backpropagate(rc::Rc::clone(&child), game::GameState::OWon);
+        tree.backpropagate(
+            child,
+            GameOutcome::Won(game::GameTurn::TurnO),
+            &SearchConfig::default(),
+            &[],
+        );
 
         // Make sure child increased only increased vists
-        assert_eq!(((*child).borrow().wins - 0.0).abs() < 1e-7, true);
-        assert_eq!(((*child).borrow().visits - 1.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[child].wins - 0.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[child].visits - 1.0).abs() < 1e-7, true);
 
         // Make sure parent increased both wins and vists
-        assert_eq!(((*root).borrow().wins - 1.0).abs() < 1e-7, true);
-        assert_eq!(((*root).borrow().visits - 1.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[ROOT].wins - 1.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[ROOT].visits - 1.0).abs() < 1e-7, true);
     }
 
     #[test]
     fn test_backpropagate_tie() {
         // Start with new game (empty board)
-        let root = MCTN::new(&game::Game::new());
+        let mut tree = MCTree::new(&game::Game::new());
 
         // X at (0, 0) added as a child
-        MCTN::play(rc::Rc::clone(&root), 0, 0);
+        let child = tree.play(ROOT, (0, 0));
 
-        assert_eq!((*root).borrow().children.len(), 1);
-        let child = rc::Rc::clone((*root).borrow().children.iter().next().unwrap());
+        assert_eq!(tree.nodes[ROOT].children.len(), 1);
 
         // Propagate state Tie up from child to parent
-        MCTN::backpropagate(rc::Rc::clone(&child), game::GameState::Tie);
+        tree.backpropagate(child, GameOutcome::Tie, &SearchConfig::default(), &[]);
 
         // Make sure child increased vists by 1 and wins by 0.5
-        assert_eq!(((*child).borrow().wins - 0.5).abs() < 1e-7, true);
-        assert_eq!(((*child).borrow().visits - 1.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[child].wins - 0.5).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[child].visits - 1.0).abs() < 1e-7, true);
 
         // Make sure parent increased vists by 1 and wins by 0.5
-        assert_eq!(((*root).borrow().wins - 0.5).abs() < 1e-7, true);
-        assert_eq!(((*root).borrow().visits - 1.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[ROOT].wins - 0.5).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[ROOT].visits - 1.0).abs() < 1e-7, true);
     }
 
     #[test]
     fn test_backpropagate_2_levels() {
         // Start with new game (empty board)
-        let root = MCTN::new(&game::Game::new());
+        let mut tree = MCTree::new(&game::Game::new());
 
         // X at (0, 0) added as a child
-        MCTN::play(rc::Rc::clone(&root), 0, 0);
-
-        assert_eq!((*root).borrow().children.len(), 1);
+        let child_level_1 = tree.play(ROOT, (0, 0));
 
-        let child_level_1 = rc::Rc::clone((*root).borrow().children.iter().next().unwrap());
+        assert_eq!(tree.nodes[ROOT].children.len(), 1);
 
         // O at (1, 1) added as a child second level
-        MCTN::play(rc::Rc::clone(&child_level_1), 1, 1);
+        let child_level_2 = tree.play(child_level_1, (1, 1));
 
-        assert_eq!((*child_level_1).borrow().children.len(), 1);
-
-        let child_level_2 =
-            rc::Rc::clone((*&child_level_1).borrow().children.iter().next().unwrap());
+        assert_eq!(tree.nodes[child_level_1].children.len(), 1);
 
         // Propagate state XWon two levels up the tree from child to parent
-        MCTN::backpropagate(rc::Rc::clone(&child_level_2), game::GameState::XWon);
+        tree.backpropagate(
+            child_level_2,
+            GameOutcome::Won(game::GameTurn::TurnX),
+            &SearchConfig::default(),
+            &[],
+        );
 
         // Make sure 2nd child increased only vists by 1
-        assert_eq!(((*child_level_2).borrow().wins - 0.0).abs() < 1e-7, true);
-        assert_eq!(((*child_level_2).borrow().visits - 1.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[child_level_2].wins - 0.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[child_level_2].visits - 1.0).abs() < 1e-7, true);
 
         // Make sure 1st child increased both vists and wins by 1
-        assert_eq!(((*child_level_1).borrow().wins - 1.0).abs() < 1e-7, true);
-        assert_eq!(((*child_level_1).borrow().visits - 1.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[child_level_1].wins - 1.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[child_level_1].visits - 1.0).abs() < 1e-7, true);
 
         // Make sure root increased only vists by 1
-        assert_eq!(((*root).borrow().wins - 0.0).abs() < 1e-7, true);
-        assert_eq!(((*root).borrow().visits - 1.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[ROOT].wins - 0.0).abs() < 1e-7, true);
+        assert_eq!((tree.nodes[ROOT].visits - 1.0).abs() < 1e-7, true);
+    }
+
+    #[test]
+    fn test_backpropagate_repeated_turn() {
+        // Under swap2, the first player places the first stones in a row
+        let rules = tic_tac_toe::gomoku::GameRules {
+            opening: tic_tac_toe::gomoku::Opening::Swap2,
+            ..Default::default()
+        };
+        let mut tree = MCTree::new(&tic_tac_toe::gomoku::Game::with_rules(rules));
+        let child_level_1 = tree.play(ROOT, tic_tac_toe::gomoku::Move::Place(7, 7));
+        let child_level_2 = tree.play(child_level_1, tic_tac_toe::gomoku::Move::Place(7, 8));
+        assert!(tree.nodes[child_level_1].repeats_turn);
+
+        tree.backpropagate(
+            child_level_2,
+            GameOutcome::Won(tic_tac_toe::gomoku::Player::First),
+            &SearchConfig::default(),
+            &[],
+        );
+
+        // Both moves were made by the first player, who won
+        assert!((tree.nodes[child_level_2].wins - 1.0).abs() < 1e-7);
+        assert!((tree.nodes[child_level_1].wins - 1.0).abs() < 1e-7);
+        assert!((tree.nodes[ROOT].wins - 0.0).abs() < 1e-7);
+
+        // The first player is to move in both nodes, so both are worth a win to the player to move
+        assert!((tree.get_value_to_move(child_level_1, 1.0) - 1.0).abs() < 1e-7);
+        assert!((tree.get_value_to_move(ROOT, 0.0) - 1.0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_backpropagate_amaf() {
+        let mut tree = MCTree::new(&game::Game::new());
+        tree.expand_node(ROOT, &SearchConfig::default());
+
+        let config = SearchConfig {
+            rave_equivalence: Some(100.0),
+            ..Default::default()
+        };
+
+        // X plays (0, 0) in the tree, then the playout continues with O at (1, 1) and X at (2, 2)...
+        let child = tree.nodes[ROOT].children[0];
+        assert_eq!(tree.nodes[child].move_from_parent, Some((0, 0)));
+        let playout_moves = [
+            (game::GameTurn::TurnO, (1, 1)),
+            (game::GameTurn::TurnX, (2, 2)),
+            (game::GameTurn::TurnO, (1, 0)),
+            (game::GameTurn::TurnX, (0, 2)),
+            (game::GameTurn::TurnO, (0, 1)),
+            (game::GameTurn::TurnX, (2, 0)),
+        ];
+
+        // ... and X wins
+        tree.backpropagate(
+            child,
+            GameOutcome::Won(game::GameTurn::TurnX),
+            &config,
+            &playout_moves,
+        );
+
+        for &root_child in tree.nodes[ROOT].children.iter() {
+            let node = &tree.nodes[root_child];
+            let played_by_x =
+                [(0, 0), (2, 2), (0, 2), (2, 0)].contains(&node.move_from_parent.unwrap());
+
+            // Only root moves that X played at some point get an AMAF update, credited with X's win
+            if played_by_x {
+                assert!((node.amaf_visits - 1.0).abs() < 1e-7);
+                assert!((node.amaf_wins - 1.0).abs() < 1e-7);
+            } else {
+                assert!((node.amaf_visits - 0.0).abs() < 1e-7);
+            }
+        }
+    }
+
+    #[test]
+    fn test_backpropagate_amaf_disabled() {
+        let mut tree = MCTree::new(&game::Game::new());
+        tree.expand_node(ROOT, &SearchConfig::default());
+
+        let child = tree.nodes[ROOT].children[0];
+        tree.backpropagate(
+            child,
+            GameOutcome::Won(game::GameTurn::TurnX),
+            &SearchConfig::default(),
+            &[
+                (game::GameTurn::TurnO, (1, 1)),
+                (game::GameTurn::TurnX, (2, 2)),
+            ],
+        );
+
+        // Plain UCT leaves AMAF statistics untouched
+        for &root_child in tree.nodes[ROOT].children.iter() {
+            assert!((tree.nodes[root_child].amaf_visits - 0.0).abs() < 1e-7);
+        }
+    }
+
+    #[test]
+    fn test_node_value_rave_blend() {
+        let mut tree = MCTree::new(&game::Game::new());
+        let child = tree.play(ROOT, (0, 0));
+        tree.nodes[child].wins = 1.0;
+        tree.nodes[child].visits = 4.0;
+        tree.nodes[child].amaf_wins = 9.0;
+        tree.nodes[child].amaf_visits = 10.0;
+
+        // Plain UCT only looks at the win rate
+        assert!((tree.node_value(child, &SearchConfig::default()) - 0.25).abs() < 1e-7);
+
+        // With k = 12 and 4 visits, beta = sqrt(12 / 24)
+        let config = SearchConfig {
+            rave_equivalence: Some(12.0),
+            ..Default::default()
+        };
+        let beta = 0.5_f64.sqrt();
+        let expected = (1.0 - beta) * 0.25 + beta * 0.9;
+        assert!((tree.node_value(child, &config) - expected).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_think_about_best_move_rave() {
+        let mut tree = MCTree::new(&game::Game::new());
+        let config = SearchConfig {
+            rave_equivalence: Some(300.0),
+            ..iterations_config(300)
+        };
+
+        assert!(tree.think_about_best_move(&config).is_some());
+        assert!(tree.nodes[ROOT]
+            .children
+            .iter()
+            .all(|&child| tree.nodes[child].amaf_visits > 0.0));
+    }
+
+    #[test]
+    fn test_advance_root_keeps_subtree() {
+        let mut tree = MCTree::new(&game::Game::new());
+        tree.think_about_best_move(&iterations_config(200));
+
+        let child = tree.nodes[ROOT].children[0];
+        let child_move = tree.nodes[child].move_from_parent.unwrap();
+        let child_game = tree.nodes[child].game.clone();
+        let child_visits = tree.nodes[child].visits;
+        let child_child_count = tree.nodes[child].children.len();
+        let node_count = tree.get_node_count();
+
+        tree.advance_root(&[child_move]).unwrap();
+
+        // The played child is now the root, with its statistics intact
+        assert!(tree.nodes[ROOT].game == child_game);
+        assert_eq!(tree.nodes[ROOT].parent, None);
+        assert_eq!(tree.nodes[ROOT].move_from_parent, None);
+        assert!((tree.get_root_visits() - child_visits).abs() < 1e-7);
+        assert_eq!(tree.nodes[ROOT].children.len(), child_child_count);
+
+        // Siblings of the played child are dropped
+        assert!(tree.get_node_count() < node_count);
+
+        // Links inside the arena are consistent after re-indexing
+        for (index, node) in tree.nodes.iter().enumerate() {
+            for &child in node.children.iter() {
+                assert_eq!(tree.nodes[child].parent, Some(index));
+            }
+        }
+    }
+
+    #[test]
+    fn test_advance_root_unexpanded_position() {
+        let mut tree = MCTree::new(&game::Game::new());
+        tree.advance_root(&[(0, 0), (1, 1)]).unwrap();
+
+        let mut expected_game = game::Game::new();
+        expected_game.play(0, 0).unwrap();
+        expected_game.play(1, 1).unwrap();
+
+        assert!(tree.nodes[ROOT].game == expected_game);
+        assert_eq!(tree.get_node_count(), 1);
+    }
+
+    #[test]
+    fn test_advance_root_illegal_move() {
+        let mut tree = MCTree::new(&game::Game::new());
+        tree.think_about_best_move(&iterations_config(10));
+        let node_count = tree.get_node_count();
+
+        assert_eq!(
+            tree.advance_root(&[(0, 0), (0, 0)]),
+            Err(AdvanceRootError::IllegalMove)
+        );
+
+        // The tree is left untouched after a failure
+        assert_eq!(tree.get_node_count(), node_count);
+    }
+
+    #[test]
+    fn test_backpropagate_draw_value() {
+        let mut tree = MCTree::new(&game::Game::new());
+        let child = tree.play(ROOT, (0, 0));
+
+        let config = SearchConfig {
+            draw_value: 0.2,
+            ..Default::default()
+        };
+        tree.backpropagate(child, GameOutcome::Tie, &config, &[]);
+
+        // Both players are credited with the configured draw value
+        assert!((tree.nodes[child].wins - 0.2).abs() < 1e-7);
+        assert!((tree.nodes[ROOT].wins - 0.2).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_think_about_best_move_time_budget() {
+        let mut tree = MCTree::new(&game::Game::new());
+        let config = SearchConfig {
+            budget: SearchBudget::Time(time::Duration::from_millis(20)),
+            ..Default::default()
+        };
+
+        let start = time::Instant::now();
+        tree.think_about_best_move(&config);
+
+        assert!(start.elapsed() >= time::Duration::from_millis(20));
+        assert!(tree.get_root_visits() > 0.0);
+    }
+
+    #[test]
+    fn test_think_about_best_move_deadline() {
+        let mut tree = MCTree::new(&game::Game::new());
+        let config = SearchConfig {
+            budget: SearchBudget::Iterations(u32::MAX),
+            limits: SearchLimits {
+                deadline: Some(time::Instant::now() + time::Duration::from_millis(20)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Search stops at the deadline long before the iteration budget is used up
+        assert!(tree.think_about_best_move(&config).is_some());
+    }
+
+    #[test]
+    fn test_think_about_best_move_max_nodes() {
+        let mut tree = MCTree::new(&game::Game::new());
+        let config = SearchConfig {
+            budget: SearchBudget::Iterations(u32::MAX),
+            limits: SearchLimits {
+                max_nodes: Some(100),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        tree.think_about_best_move(&config);
+
+        // The final expansion adds at most 9 children past the cap
+        assert!(tree.get_node_count() >= 100);
+        assert!(tree.get_node_count() < 100 + 9);
+    }
+
+    #[test]
+    fn test_think_about_best_move_stop_flag() {
+        use std::sync::{self, atomic};
+        use std::thread;
+
+        let stop_flag = sync::Arc::new(atomic::AtomicBool::new(false));
+        let config = SearchConfig {
+            budget: SearchBudget::Iterations(u32::MAX),
+            limits: SearchLimits {
+                stop_flag: Some(sync::Arc::clone(&stop_flag)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let stopper = thread::spawn(move || {
+            thread::sleep(time::Duration::from_millis(20));
+            stop_flag.store(true, atomic::Ordering::Relaxed);
+        });
+
+        let mut tree = MCTree::new(&game::Game::new());
+        assert!(tree.think_about_best_move(&config).is_some());
+        stopper.join().unwrap();
+    }
+
+    /// Builds a root with children (0, 0), (1, 1) and (2, 2) holding the given (wins, visits)
+    fn tree_with_root_stats(stats: [(f64, f64); 3]) -> MCTree<game::Game> {
+        let mut tree = MCTree::new(&game::Game::new());
+
+        for (game_move, (wins, visits)) in [(0, 0), (1, 1), (2, 2)].into_iter().zip(stats) {
+            let child = tree.play(ROOT, game_move);
+            tree.nodes[child].wins = wins;
+            tree.nodes[child].visits = visits;
+            tree.nodes[ROOT].visits += visits;
+        }
+
+        tree
+    }
+
+    fn policy_config(final_move_policy: FinalMovePolicy) -> SearchConfig<game::Game> {
+        SearchConfig {
+            final_move_policy,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_select_best_move_policies() {
+        // (1, 1) has the best win rate from few visits, (0, 0) has the most visits
+        let tree = tree_with_root_stats([(60.0, 100.0), (4.0, 5.0), (10.0, 20.0)]);
+
+        let max_child = tree.select_best_move(&policy_config(FinalMovePolicy::MaxChild));
+        assert_eq!(max_child, Some((1, 1)));
+
+        let robust_child = tree.select_best_move(&policy_config(FinalMovePolicy::RobustChild));
+        assert_eq!(robust_child, Some((0, 0)));
+
+        // No child is best on both counts, so max-robust falls back to the robust child
+        let max_robust_child =
+            tree.select_best_move(&policy_config(FinalMovePolicy::MaxRobustChild));
+        assert_eq!(max_robust_child, Some((0, 0)));
+
+        // The few visits of (1, 1) give it a wide confidence interval, so the secure child is (0, 0)
+        let secure_child = tree.select_best_move(&policy_config(FinalMovePolicy::SecureChild));
+        assert_eq!(secure_child, Some((0, 0)));
+    }
+
+    #[test]
+    fn test_select_best_move_max_robust_agreement() {
+        let tree = tree_with_root_stats([(60.0, 100.0), (2.0, 5.0), (80.0, 120.0)]);
+
+        let max_robust_child =
+            tree.select_best_move(&policy_config(FinalMovePolicy::MaxRobustChild));
+        assert_eq!(max_robust_child, Some((2, 2)));
+    }
+
+    #[test]
+    fn test_select_best_move_all_losing() {
+        // Every child has a zero win rate, yet a move is still returned
+        let tree = tree_with_root_stats([(0.0, 10.0), (0.0, 30.0), (0.0, 20.0)]);
+
+        for final_move_policy in [
+            FinalMovePolicy::MaxChild,
+            FinalMovePolicy::RobustChild,
+            FinalMovePolicy::MaxRobustChild,
+            FinalMovePolicy::SecureChild,
+        ] {
+            assert!(tree
+                .select_best_move(&policy_config(final_move_policy))
+                .is_some());
+        }
+    }
+
+    #[test]
+    fn test_think_about_best_move_without_iterations() {
+        let mut tree = MCTree::new(&game::Game::new());
+
+        // The root is expanded so that a move is returned even without any search
+        assert!(tree.think_about_best_move(&iterations_config(0)).is_some());
+    }
+
+    #[test]
+    fn test_think_about_best_move_game_over() {
+        let mut game = game::Game::new();
+        for (row_index, col_index) in [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+            game.play(row_index, col_index).unwrap();
+        }
+
+        let mut tree = MCTree::new(&game);
+        assert_eq!(tree.think_about_best_move(&iterations_config(10)), None);
+    }
+
+    /// Move from parent, wins and visits of a node
+    type NodeStats = (Option<(usize, usize)>, f64, f64);
+
+    /// Collects the stats of every node in arena order
+    fn tree_stats(tree: &MCTree<game::Game>) -> Vec<NodeStats> {
+        tree.nodes
+            .iter()
+            .map(|node| (node.move_from_parent, node.wins, node.visits))
+            .collect()
+    }
+
+    #[test]
+    fn test_think_about_best_move_same_seed() {
+        let config = SearchConfig {
+            seed: Some(7),
+            ..iterations_config(300)
+        };
+
+        let mut first_tree = MCTree::new(&game::Game::new());
+        let first_move = first_tree.think_about_best_move(&config);
+
+        let mut second_tree = MCTree::new(&game::Game::new());
+        let second_move = second_tree.think_about_best_move(&config);
+
+        // Same seed on the same position gives the same tree and the same move
+        assert_eq!(first_move, second_move);
+        assert_eq!(tree_stats(&first_tree), tree_stats(&second_tree));
+    }
+
+    #[test]
+    fn test_think_about_best_move_different_seeds() {
+        let mut first_tree = MCTree::new(&game::Game::new());
+        first_tree.think_about_best_move(&SearchConfig {
+            seed: Some(1),
+            ..iterations_config(300)
+        });
+
+        let mut second_tree = MCTree::new(&game::Game::new());
+        second_tree.think_about_best_move(&SearchConfig {
+            seed: Some(2),
+            ..iterations_config(300)
+        });
+
+        assert_ne!(tree_stats(&first_tree), tree_stats(&second_tree));
+    }
+
+    #[test]
+    fn test_think_about_best_move_with_rng() {
+        use rand::SeedableRng;
+
+        let config = iterations_config(300);
+
+        let mut first_tree = MCTree::new(&game::Game::new());
+        let mut first_rng = rand::rngs::StdRng::seed_from_u64(11);
+        let first_move = first_tree.think_about_best_move_with_rng(&config, &mut first_rng);
+
+        let mut second_tree = MCTree::new(&game::Game::new());
+        let mut second_rng = rand::rngs::StdRng::seed_from_u64(11);
+        let second_move = second_tree.think_about_best_move_with_rng(&config, &mut second_rng);
+
+        assert_eq!(first_move, second_move);
+        assert_eq!(tree_stats(&first_tree), tree_stats(&second_tree));
+    }
+
+    #[test]
+    fn test_merge_root_stats() {
+        let first_tree = tree_with_root_stats([(1.0, 2.0), (3.0, 4.0), (5.0, 6.0)]);
+        let second_tree = tree_with_root_stats([(10.0, 20.0), (30.0, 40.0), (50.0, 60.0)]);
+
+        let (children, root_visits) = MCTree::merge_root_stats(&[&first_tree, &second_tree]);
+
+        assert_eq!(
+            children,
+            vec![
+                ((0, 0), 11.0, 22.0),
+                ((1, 1), 33.0, 44.0),
+                ((2, 2), 55.0, 66.0)
+            ]
+        );
+        assert!((root_visits - 132.0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_think_about_best_move_root_parallel() {
+        let config = SearchConfig {
+            parallelism: Parallelism::RootParallel { threads: 4 },
+            seed: Some(3),
+            ..iterations_config(200)
+        };
+
+        let mut first_tree = MCTree::new(&game::Game::new());
+        let first_move = first_tree.think_about_best_move(&config);
+        assert!(game::Game::new()
+            .get_possible_plays()
+            .contains(&first_move.unwrap()));
+
+        // Every thread is seeded from the config seed, so the merged result is reproducible
+        let mut second_tree = MCTree::new(&game::Game::new());
+        let second_move = second_tree.think_about_best_move(&config);
+        assert_eq!(first_move, second_move);
+        assert_eq!(tree_stats(&first_tree), tree_stats(&second_tree));
+    }
+
+    #[test]
+    fn test_search_report() {
+        let config = SearchConfig {
+            seed: Some(4),
+            ..iterations_config(300)
+        };
+        let mut tree = MCTree::new(&game::Game::new());
+        let report = tree.search(&config);
+
+        assert_eq!(report.iterations, 300);
+        assert_eq!(report.node_count, tree.get_node_count());
+        assert_eq!(report.max_depth, tree.get_max_depth());
+        assert!((report.root_visits - tree.get_root_visits()).abs() < 1e-7);
+        assert!(report.root_value >= 0.0 && report.root_value <= 1.0);
+
+        // Root moves come by decreasing visits, and their visits add up to those of the root
+        assert_eq!(report.root_moves.len(), 9);
+        assert!(report
+            .root_moves
+            .windows(2)
+            .all(|pair| pair[0].visits >= pair[1].visits));
+        let total_visits: f64 = report.root_moves.iter().map(|stats| stats.visits).sum();
+        assert!((total_visits - report.root_visits).abs() < 1e-7);
+
+        for stats in report.root_moves.iter() {
+            assert!(stats.ucb > stats.mean_value);
+        }
+
+        // The principal variation starts with the picked move and is a legal line
+        assert_eq!(report.principal_variation[0], report.best_move.unwrap());
+        assert!(report.principal_variation.len() <= report.max_depth);
+        let mut game = game::Game::new();
+        for &game_move in report.principal_variation.iter() {
+            assert!(game.get_possible_plays().contains(&game_move));
+            game.play_move(game_move);
+        }
+
+        // With the same seed, the move is the same as the one `think_about_best_move` picks
+        let mut other_tree = MCTree::new(&game::Game::new());
+        assert_eq!(other_tree.think_about_best_move(&config), report.best_move);
+    }
+
+    #[test]
+    fn test_search_report_proven_moves() {
+        // X to move can win with (0, 2), which is proven as soon as it is added
+        let mut tree = MCTree::new(&game_after(&[(0, 0), (1, 0), (0, 1), (1, 1)]));
+        let report = tree.search(&solver_config(100));
+
+        assert_eq!(report.best_move, Some((0, 2)));
+        assert_eq!(report.principal_variation, vec![(0, 2)]);
+
+        let winning_stats = report
+            .root_moves
+            .iter()
+            .find(|stats| stats.game_move == (0, 2))
+            .unwrap();
+        assert_eq!(
+            winning_stats.proven,
+            Some(GameOutcome::Won(game::GameTurn::TurnX))
+        );
+    }
+
+    #[test]
+    fn test_search_report_game_over() {
+        let mut tree = MCTree::new(&game_after(&[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]));
+        let report = tree.search(&iterations_config(10));
+
+        assert_eq!(report.best_move, None);
+        assert!(report.root_moves.is_empty());
+        assert!(report.principal_variation.is_empty());
+        assert_eq!(report.max_depth, 0);
+    }
+
+    #[test]
+    fn test_search_report_root_parallel() {
+        let config = SearchConfig {
+            parallelism: Parallelism::RootParallel { threads: 2 },
+            seed: Some(5),
+            ..iterations_config(100)
+        };
+        let mut tree = MCTree::new(&game::Game::new());
+        let report = tree.search(&config);
+
+        // Root statistics, tree sizes and iterations cover both trees
+        assert_eq!(report.iterations, 200);
+        assert!(report.node_count > tree.get_node_count());
+        assert!(report.root_visits > tree.get_root_visits());
+        assert_eq!(report.principal_variation[0], report.best_move.unwrap());
+    }
+
+    #[test]
+    fn test_think_about_best_move() {
+        let mut tree = MCTree::new(&game::Game::new());
+        let best_move = tree.think_about_best_move(&iterations_config(200));
+
+        assert!(game::Game::new()
+            .get_possible_plays()
+            .contains(&best_move.unwrap()));
+
+        // Every non-root node received exactly one playout when it was added
+        assert!((tree.get_root_visits() - (tree.get_node_count() - 1) as f64).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_backpropagate_squared_rewards() {
+        let mut tree = MCTree::new(&game::Game::new());
+        tree.expand_node(ROOT, &SearchConfig::default());
+        let a_child = tree.nodes[ROOT].children[0];
+
+        let config = SearchConfig::default();
+        tree.backpropagate(
+            a_child,
+            GameOutcome::Won(game::GameTurn::TurnX),
+            &config,
+            &[],
+        );
+        tree.backpropagate(a_child, GameOutcome::Tie, &config, &[]);
+
+        // X moved into the child, and got rewards 1 and 0.5
+        assert!((tree.nodes[a_child].squared_rewards - 1.25).abs() < 1e-7);
+        assert!((tree.nodes[ROOT].squared_rewards - 0.25).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_think_about_best_move_tree_policies() {
+        // X to move can win with (0, 2)
+        let game = game_after(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        let tree_policies: Vec<sync::Arc<dyn TreePolicy>> = vec![
+            sync::Arc::new(Uct),
+            sync::Arc::new(Ucb1Tuned),
+            sync::Arc::new(UcbV::default()),
+            sync::Arc::new(ThompsonSampling),
+        ];
+
+        for tree_policy in tree_policies {
+            let config = SearchConfig {
+                tree_policy: Some(tree_policy),
+                ..iterations_config(500)
+            };
+            let mut tree = MCTree::new(&game);
+
+            assert_eq!(tree.think_about_best_move(&config), Some((0, 2)));
+        }
+    }
+
+    #[test]
+    fn test_think_about_best_move_larger_board() {
+        // On a 4x4 board with 3 in a row, X to move wins with (3, 3), while O threatens to win at (0, 2)
+        let mut game = game::Game::with_size(4, 4, 3).unwrap();
+        for (row_index, col_index) in [(1, 1), (0, 0), (2, 2), (0, 1)] {
+            game.play(row_index, col_index).unwrap();
+        }
+
+        let config = SearchConfig {
+            solver: true,
+            ..iterations_config(2000)
+        };
+        let mut tree = MCTree::new(&game);
+        let best_move = tree.think_about_best_move(&config).unwrap();
+
+        assert_eq!(best_move, (3, 3));
+        assert_eq!(tree.get_root_child_visits().len(), 12);
+    }
+
+    #[test]
+    fn test_think_about_best_move_gomoku() {
+        // X has an open four along row 7 and wins at either end
+        let mut game = tic_tac_toe::gomoku::Game::new();
+        for location in [
+            (7, 3),
+            (0, 0),
+            (7, 4),
+            (0, 2),
+            (7, 5),
+            (0, 4),
+            (7, 6),
+            (0, 6),
+        ] {
+            game.play(tic_tac_toe::gomoku::Move::Place(location.0, location.1))
+                .unwrap();
+        }
+
+        let config = SearchConfig {
+            solver: true,
+            expansion: ExpansionMode::Single { threshold: 0 },
+            budget: SearchBudget::Iterations(400),
+            seed: Some(0),
+            ..Default::default()
+        };
+        let mut tree = MCTree::new(&game);
+        let report = tree.search(&config);
+
+        assert!(matches!(
+            report.best_move,
+            Some(tic_tac_toe::gomoku::Move::Place(7, 2) | tic_tac_toe::gomoku::Move::Place(7, 7))
+        ));
+    }
+
+    #[test]
+    fn test_think_about_best_move_connect_four() {
+        // Red completes the bottom row with column 3, and must otherwise block Yellow right above it
+        let mut game = connect_four::game::Game::new();
+        for col_index in [0, 0, 1, 1, 2, 2] {
+            game.play(col_index).unwrap();
+        }
+
+        let config = SearchConfig {
+            solver: true,
+            budget: SearchBudget::Iterations(1000),
+            seed: Some(0),
+            ..Default::default()
+        };
+        let mut tree = MCTree::new(&game);
+        let report = tree.search(&config);
+
+        assert_eq!(report.best_move, Some(3));
+        let winning_move = report
+            .root_moves
+            .iter()
+            .find(|move_stats| move_stats.game_move == 3)
+            .unwrap();
+        assert_eq!(
+            winning_move.proven,
+            Some(GameOutcome::Won(connect_four::game::GameTurn::TurnRed))
+        );
+    }
+
+    #[test]
+    fn test_think_about_best_move_ultimate_tic_tac_toe() {
+        // X holds the top left and centre sub-boards of the meta-board and is sent to the nearly won bottom right
+        // one, where (8, 8) wins the game
+        let mut game = tic_tac_toe::ultimate::Game::new();
+        for (row_index, col_index) in [
+            (1, 0),
+            (3, 0),
+            (1, 1),
+            (3, 3),
+            (1, 2),
+            (3, 6),
+            (4, 3),
+            (4, 1),
+            (4, 5),
+            (4, 7),
+            (4, 4),
+            (8, 2),
+            (6, 6),
+            (8, 5),
+            (7, 7),
+            (2, 5),
+        ] {
+            game.play(row_index, col_index).unwrap();
+        }
+        assert_eq!(game.get_next_board(), Some((2, 2)));
+
+        let config = SearchConfig {
+            solver: true,
+            budget: SearchBudget::Iterations(1000),
+            seed: Some(0),
+            ..Default::default()
+        };
+        let mut tree = MCTree::new(&game);
+        let report = tree.search(&config);
+
+        assert_eq!(report.best_move, Some((8, 8)));
+    }
+
+    /// Plays `moves` from an empty board
+    fn game_after(moves: &[(usize, usize)]) -> game::Game {
+        let mut game = game::Game::new();
+        for &(row_index, col_index) in moves {
+            game.play(row_index, col_index).unwrap();
+        }
+        game
+    }
+
+    fn solver_config(iterations: u32) -> SearchConfig<game::Game> {
+        SearchConfig {
+            solver: true,
+            ..iterations_config(iterations)
+        }
+    }
+
+    #[test]
+    fn test_play_proves_terminal_node() {
+        // X can complete the first row at (0, 2)
+        let mut tree = MCTree::new(&game_after(&[(0, 0), (1, 0), (0, 1), (1, 1)]));
+
+        let winning_child = tree.play(ROOT, (0, 2));
+        let other_child = tree.play(ROOT, (2, 2));
+
+        assert_eq!(
+            tree.nodes[winning_child].proven,
+            Some(GameOutcome::Won(game::GameTurn::TurnX))
+        );
+        assert_eq!(tree.nodes[other_child].proven, None);
+    }
+
+    #[test]
+    fn test_backpropagate_proves_win() {
+        let mut tree = MCTree::new(&game_after(&[(0, 0), (1, 0), (0, 1), (1, 1)]));
+        tree.expand_node(ROOT, &SearchConfig::default());
+
+        let winning_child = tree.nodes[ROOT]
+            .children
+            .iter()
+            .copied()
+            .find(|&child| tree.nodes[child].move_from_parent == Some((0, 2)))
+            .unwrap();
+
+        // Without the solver, nothing is proven past the terminal node itself
+        let result = GameOutcome::Won(game::GameTurn::TurnX);
+        tree.backpropagate(winning_child, result, &iterations_config(1), &[]);
+        assert_eq!(tree.nodes[ROOT].proven, None);
+
+        // A single winning child is enough to prove the root
+        tree.backpropagate(winning_child, result, &solver_config(1), &[]);
+        assert_eq!(tree.nodes[ROOT].proven, Some(result));
+    }
+
+    #[test]
+    fn test_think_about_best_move_solver_proves_loss() {
+        // O to move with (2, 1) and (2, 2) left, and X wins with whichever one O leaves
+        let game = game_after(&[(0, 0), (0, 2), (0, 1), (1, 0), (1, 1), (2, 0), (1, 2)]);
+        let mut tree = MCTree::new(&game);
+
+        let best_move = tree.think_about_best_move(&solver_config(100));
+
+        assert!(game.get_possible_plays().contains(&best_move.unwrap()));
+        assert_eq!(
+            tree.nodes[ROOT].proven,
+            Some(GameOutcome::Won(game::GameTurn::TurnX))
+        );
+
+        // One iteration expands the root, one proves each child lost for O, and then the search stops
+        assert!((tree.get_root_visits() - 4.0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_think_about_best_move_solver_plays_proven_win() {
+        let game = game_after(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+
+        for seed in 0..10 {
+            let mut tree = MCTree::new(&game);
+            let config = SearchConfig {
+                seed: Some(seed),
+                ..solver_config(20)
+            };
+
+            assert_eq!(tree.think_about_best_move(&config), Some((0, 2)));
+        }
+    }
+
+    #[test]
+    fn test_think_about_best_move_misere() {
+        // Under misère rules, X to move loses by completing the first row at (0, 2)
+        let mut game = game::Game::with_rules(3, 3, 3, game::GameRules::Misere).unwrap();
+        for (row_index, col_index) in [(0, 0), (1, 1), (0, 1), (2, 2)] {
+            game.play(row_index, col_index).unwrap();
+        }
+
+        let mut tree = MCTree::new(&game);
+        let best_move = tree.think_about_best_move(&solver_config(300));
+        assert_ne!(best_move, Some((0, 2)));
+
+        // The completing move is proven lost for X, which leaves O as the winner
+        let losing_child = tree.nodes[ROOT]
+            .children
+            .iter()
+            .copied()
+            .find(|&child| tree.nodes[child].move_from_parent == Some((0, 2)))
+            .unwrap();
+        assert_eq!(
+            tree.nodes[losing_child].proven,
+            Some(GameOutcome::Won(game::GameTurn::TurnO))
+        );
+        assert!(tree.nodes[losing_child].wins < 1e-7);
+    }
+
+    #[test]
+    fn test_think_about_best_move_wild() {
+        // Under wild rules, X to move wins by placing an O at (0, 2), next to the two Os of the first row
+        let mut game = WildGame(game::Game::with_rules(3, 3, 3, game::GameRules::Wild).unwrap());
+        for game_move in [(0, 0, O), (1, 1, X), (0, 1, O), (2, 2, X)] {
+            game.play_move(game_move);
+        }
+        let config = SearchConfig {
+            solver: true,
+            budget: SearchBudget::Iterations(300),
+            ..Default::default()
+        };
+
+        let mut tree = MCTree::new(&game);
+        assert_eq!(tree.think_about_best_move(&config), Some((0, 2, O)));
+        assert_eq!(
+            tree.nodes[ROOT].proven,
+            Some(GameOutcome::Won(game::GameTurn::TurnX))
+        );
+    }
+
+    #[test]
+    fn test_think_about_best_move_notakto() {
+        // On a single board, the first player wins Notakto by taking the centre
+        let game = game::Game::with_rules(3, 3, 3, game::GameRules::Notakto { boards: 1 }).unwrap();
+        let config = SearchConfig {
+            merge_symmetries: true,
+            ..solver_config(5000)
+        };
+
+        let mut tree = MCTree::new(&game);
+        assert_eq!(tree.think_about_best_move(&config), Some((1, 1)));
+        assert_eq!(
+            tree.nodes[ROOT].proven,
+            Some(GameOutcome::Won(game::GameTurn::TurnX))
+        );
+    }
+
+    #[test]
+    fn test_select_best_move_avoids_proven_loss() {
+        // The first child has the best win rate but is proven lost for X, who is to move at the root
+        let mut tree = tree_with_root_stats([(9.0, 10.0), (4.0, 10.0), (1.0, 10.0)]);
+        let first_child = tree.nodes[ROOT].children[0];
+        tree.nodes[first_child].proven = Some(GameOutcome::Won(game::GameTurn::TurnO));
+
+        assert_eq!(tree.select_best_move(&iterations_config(0)), Some((0, 0)));
+        assert_eq!(tree.select_best_move(&solver_config(0)), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_expand_node_merge_symmetries() {
+        let config = SearchConfig {
+            merge_symmetries: true,
+            ..Default::default()
+        };
+
+        let mut tree = MCTree::new(&game::Game::new());
+        tree.expand_node(ROOT, &config);
+
+        let child_moves: Vec<(usize, usize)> = tree.nodes[ROOT]
+            .children
+            .iter()
+            .map(|&child| tree.nodes[child].move_from_parent.unwrap())
+            .collect();
+        assert_eq!(child_moves, vec![(0, 0), (0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn test_think_about_best_move_merge_symmetries() {
+        // X took the bottom edge, so the search only sees one of each mirrored pair of moves for O
+        let mut game = game::Game::new();
+        game.play(2, 1).unwrap();
+
+        let config = SearchConfig {
+            merge_symmetries: true,
+            ..iterations_config(300)
+        };
+        let mut tree = MCTree::new(&game);
+        let mut full_tree = MCTree::new(&game);
+
+        let best_move = tree.think_about_best_move(&config);
+        full_tree.think_about_best_move(&iterations_config(300));
+
+        // The chosen move is a real move of the position
+        assert!(game.get_possible_plays().contains(&best_move.unwrap()));
+        assert_eq!(tree.nodes[ROOT].children.len(), 5);
+        assert!(tree.get_node_count() < full_tree.get_node_count());
+    }
+
+    fn single_expansion_config(iterations: u32, threshold: u32) -> SearchConfig<game::Game> {
+        SearchConfig {
+            expansion: ExpansionMode::Single { threshold },
+            ..iterations_config(iterations)
+        }
+    }
+
+    #[test]
+    fn test_single_expansion_one_playout_per_iteration() {
+        let mut tree = MCTree::new(&game::Game::new());
+        let best_move = tree.think_about_best_move(&single_expansion_config(50, 0));
+
+        assert!(game::Game::new()
+            .get_possible_plays()
+            .contains(&best_move.unwrap()));
+
+        // Every iteration adds at most one node and runs exactly one playout
+        assert!((tree.get_root_visits() - 50.0).abs() < 1e-7);
+        assert!(tree.get_node_count() <= 51);
+
+        // The root has been fully expanded, so its untried moves are used up
+        assert_eq!(tree.nodes[ROOT].children.len(), 9);
+        assert!(tree.is_fully_expanded(ROOT));
+    }
+
+    #[test]
+    fn test_single_expansion_threshold() {
+        let config = single_expansion_config(0, 3);
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let mut tree = MCTree::new(&game::Game::new());
+
+        // Playouts start from the root itself until it has 3 visits
+        for _ in 0..3 {
+            tree.mcts_update(&config, &mut rng);
+        }
+        assert_eq!(tree.get_node_count(), 1);
+
+        tree.mcts_update(&config, &mut rng);
+        assert_eq!(tree.get_node_count(), 2);
+        assert_eq!(tree.nodes[ROOT].untried_moves.as_ref().unwrap().len(), 8);
+
+        // The new child is not expanded before reaching the threshold itself
+        let child = tree.nodes[ROOT].children[0];
+        assert!((tree.nodes[child].visits - 1.0).abs() < 1e-7);
+        assert!(tree.nodes[child].children.is_empty());
+    }
+
+    #[test]
+    fn test_single_expansion_solver_waits_for_untried_moves() {
+        // O to move with (2, 1) and (2, 2) left, and X wins with whichever one O leaves
+        let game = game_after(&[(0, 0), (0, 2), (0, 1), (1, 0), (1, 1), (2, 0), (1, 2)]);
+        let mut tree = MCTree::new(&game);
+        let config = SearchConfig {
+            solver: true,
+            ..single_expansion_config(100, 0)
+        };
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+
+        // The first child proven lost does not prove the root while the other move is untried
+        let child = tree.expand_single_child(ROOT, &config, &mut rng).unwrap();
+        let terminal_child = tree.expand_single_child(child, &config, &mut rng).unwrap();
+        let result = GameOutcome::Won(game::GameTurn::TurnX);
+        tree.backpropagate(terminal_child, result, &config, &[]);
+
+        assert_eq!(tree.nodes[child].proven, Some(result));
+        assert_eq!(tree.nodes[ROOT].proven, None);
+
+        tree.think_about_best_move_with_rng(&config, &mut rng);
+        assert_eq!(
+            tree.nodes[ROOT].proven,
+            Some(GameOutcome::Won(game::GameTurn::TurnX))
+        );
+    }
+
+    #[test]
+    fn test_expand_node_after_single_expansion() {
+        let config = single_expansion_config(0, 0);
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let mut tree = MCTree::new(&game::Game::new());
+
+        tree.expand_single_child(ROOT, &config, &mut rng);
+        tree.expand_single_child(ROOT, &config, &mut rng);
+
+        // Expanding all children at once only adds the moves still untried
+        tree.expand_node(ROOT, &iterations_config(0));
+        assert_eq!(tree.nodes[ROOT].children.len(), 9);
+        assert!(tree.is_fully_expanded(ROOT));
+    }
+
+    /// Puts all the prior on `favorite_move` whenever it is legal, and values every game state as a draw
+    struct FavoriteMoveEvaluator {
+        favorite_move: (usize, usize),
+    }
+
+    impl Evaluator<game::Game> for FavoriteMoveEvaluator {
+        fn evaluate(
+            &self,
+            game: &game::Game,
+            _config: &SearchConfig<game::Game>,
+            _rng: &mut dyn rand::RngCore,
+        ) -> Evaluation<(usize, usize)> {
+            let priors = game
+                .get_possible_plays()
+                .into_iter()
+                .map(|game_move| {
+                    (
+                        game_move,
+                        if game_move == self.favorite_move {
+                            1.0
+                        } else {
+                            0.0
+                        },
+                    )
+                })
+                .collect();
+
+            Evaluation { priors, value: 0.5 }
+        }
+    }
+
+    fn evaluator_config(
+        evaluator: sync::Arc<dyn Evaluator<game::Game>>,
+        iterations: u32,
+    ) -> SearchConfig<game::Game> {
+        SearchConfig {
+            evaluator: Some(evaluator),
+            ..iterations_config(iterations)
+        }
+    }
+
+    #[test]
+    fn test_select_node_puct_follows_priors() {
+        let config = evaluator_config(sync::Arc::new(RolloutEvaluator), 0);
+        let mut tree = MCTree::new(&game::Game::new());
+
+        let priors = [((0, 0), 0.05), ((1, 1), 0.15), ((2, 2), 0.8)];
+        tree.expand_node_with_priors(ROOT, &priors, &config);
+        tree.backpropagate_value(ROOT, 0.5, &config);
+
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let selected_child = tree.select_node(ROOT, &config, &mut rng);
+        assert_eq!(tree.nodes[selected_child].move_from_parent, Some((2, 2)));
+
+        // Moves missing from the priors get none
+        let unlisted_child = tree.nodes[ROOT].children[1];
+        assert!((tree.nodes[unlisted_child].prior - 0.0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_backpropagate_value() {
+        let mut tree = MCTree::new(&game::Game::new());
+        let child = tree.play(ROOT, (0, 0));
+
+        // The value is for O, who is to move in the child
+        tree.backpropagate_value(child, 0.8, &SearchConfig::default());
+
+        assert!((tree.nodes[child].wins - 0.2).abs() < 1e-7);
+        assert!((tree.nodes[ROOT].wins - 0.8).abs() < 1e-7);
+        assert!((tree.nodes[ROOT].visits - 1.0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_think_about_best_move_evaluator() {
+        let evaluator = FavoriteMoveEvaluator {
+            favorite_move: (2, 0),
+        };
+        let config = SearchConfig {
+            final_move_policy: FinalMovePolicy::RobustChild,
+            ..evaluator_config(sync::Arc::new(evaluator), 100)
+        };
+
+        let mut tree = MCTree::new(&game::Game::new());
+        assert_eq!(tree.think_about_best_move(&config), Some((2, 0)));
+
+        // Every iteration evaluates a single leaf
+        assert!((tree.get_root_visits() - 100.0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_think_about_best_move_rollout_evaluator() {
+        let config = evaluator_config(sync::Arc::new(RolloutEvaluator), 200);
+        let mut tree = MCTree::new(&game::Game::new());
+        let best_move = tree.think_about_best_move(&config);
+
+        assert!(game::Game::new()
+            .get_possible_plays()
+            .contains(&best_move.unwrap()));
+        assert!((tree.get_root_visits() - 200.0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_search_keeps_visiting_terminal_leaves() {
+        // Five moves in, every branch soon ends the game, so most iterations select a terminal leaf
+        let game = game_after(&[(0, 0), (1, 1), (2, 2), (0, 2), (2, 0)]);
+
+        let mut previous_root_visits = 0.0;
+        for iterations in [10, 100, 1000] {
+            let mut tree = MCTree::new(&game);
+            let report = tree.search(&iterations_config(iterations));
+
+            // Every iteration adds at least one visit to the root
+            assert!(report.root_visits >= iterations as f64);
+            assert!(report.root_visits > previous_root_visits);
+            previous_root_visits = report.root_visits;
+        }
+    }
+
+    #[test]
+    fn test_check_config_rejects_tree_parallelism() {
+        let config = SearchConfig {
+            parallelism: Parallelism::TreeParallel { threads: 4 },
+            ..iterations_config(10)
+        };
+
+        assert_eq!(
+            MCTree::check_config(&config),
+            Err(SearchConfigError::UnsupportedParallelism)
+        );
+        assert_eq!(MCTree::check_config(&iterations_config(10)), Ok(()));
     }
 }
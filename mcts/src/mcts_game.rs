@@ -0,0 +1,453 @@
+//! Contains the game abstraction that MCTS (Monte Carlo Tree Search) searches over
+
+use connect_four::game as connect_four_game;
+use std::fmt;
+use tic_tac_toe::board;
+use tic_tac_toe::game;
+use tic_tac_toe::gomoku;
+use tic_tac_toe::ultimate;
+
+/// Represents the final outcome of a game that is over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameOutcome<P> {
+    Won(P),
+    Tie,
+}
+
+/// Represents a two-player, turn-based game with perfect information that can be searched with MCTS.
+///
+/// Implementors must keep `get_turn` switching to the other player after the final move of the game, so
+/// that a terminal state reports the player who did *not* make the last move. Players usually alternate, but a
+/// player may also make several moves in a row, as in some opening protocols. Games must be `Send` and `Sync`
+/// so that they can be searched from several threads.
+pub trait MCTSGame: Clone + Send + Sync {
+    /// A single move in the game
+    type Move: Copy + PartialEq + fmt::Debug + Send + Sync;
+    /// Identifies one of the two players
+    type Player: Copy + PartialEq + fmt::Debug + Send + Sync;
+
+    /// Returns all legal moves from current state. Must be empty if and only if the game is over
+    fn get_possible_plays(&self) -> Vec<Self::Move>;
+
+    /// Plays `game_move` as the current player. `game_move` must be one of `get_possible_plays`
+    fn play_move(&mut self, game_move: Self::Move);
+
+    /// Gets the player whose turn it is
+    fn get_turn(&self) -> Self::Player;
+
+    /// Returns the outcome of the game, or `None` if the game is still ongoing
+    fn get_outcome(&self) -> Option<GameOutcome<Self::Player>>;
+
+    /// Returns a boolean indicating whether the game is over
+    fn is_over(&self) -> bool {
+        self.get_outcome().is_some()
+    }
+
+    /// Returns a copy of the game after `game_move` has been played
+    fn get_played_move(&self, game_move: Self::Move) -> Self {
+        let mut cloned_game = self.clone();
+        cloned_game.play_move(game_move);
+        cloned_game
+    }
+
+    /// Returns the legal moves from current state, keeping a single move out of every group of moves that lead
+    /// to symmetric positions. Kept moves are real moves of current state. Defaults to all legal moves, for games
+    /// without symmetries
+    fn get_distinct_plays(&self) -> Vec<Self::Move> {
+        self.get_possible_plays()
+    }
+}
+
+/// Panics if `game` follows wild rules, whose moves also pick the mark to place. Searching such a game with
+/// `(usize, usize)` moves would leave out the opponent's mark, so it must be wrapped in a `WildGame` instead
+fn assert_not_wild(game: &game::Game) {
+    assert!(
+        game.get_rules() != game::GameRules::Wild,
+        "Wild Tic-Tac-Toe must be searched as a WildGame."
+    );
+}
+
+impl MCTSGame for game::Game {
+    /// The location (row_index, col_index) of the cell to mark with the mark given by `get_mark`. Games following
+    /// wild rules cannot be searched this way and make move generation panic, see `WildGame`
+    type Move = (usize, usize);
+    type Player = game::GameTurn;
+
+    fn get_possible_plays(&self) -> Vec<(usize, usize)> {
+        assert_not_wild(self);
+        game::Game::get_possible_plays(self)
+    }
+
+    fn play_move(&mut self, (row_index, col_index): (usize, usize)) {
+        self.play(row_index, col_index).unwrap();
+    }
+
+    fn get_distinct_plays(&self) -> Vec<(usize, usize)> {
+        assert_not_wild(self);
+        let mut canonical_games: Vec<game::Game> = Vec::new();
+
+        game::Game::get_possible_plays(self)
+            .into_iter()
+            .filter(|&(row_index, col_index)| {
+                let (canonical_game, _) = self
+                    .get_played(row_index, col_index)
+                    .unwrap()
+                    .get_canonical();
+                if canonical_games.contains(&canonical_game) {
+                    return false;
+                }
+
+                canonical_games.push(canonical_game);
+                true
+            })
+            .collect()
+    }
+
+    fn get_turn(&self) -> game::GameTurn {
+        game::Game::get_turn(self)
+    }
+
+    fn get_outcome(&self) -> Option<GameOutcome<game::GameTurn>> {
+        match self.get_state() {
+            game::GameState::Ongoing => None,
+            game::GameState::XWon => Some(GameOutcome::Won(game::GameTurn::TurnX)),
+            game::GameState::OWon => Some(GameOutcome::Won(game::GameTurn::TurnO)),
+            game::GameState::Tie => Some(GameOutcome::Tie),
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        game::Game::is_over(self)
+    }
+}
+
+/// Wraps a Tic-Tac-Toe game following wild rules, so that it is searched with moves that also pick the mark to
+/// place
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct WildGame(pub game::Game);
+
+impl MCTSGame for WildGame {
+    /// The location (row_index, col_index) of the cell to mark, and the mark placed there
+    type Move = (usize, usize, board::Cell);
+    type Player = game::GameTurn;
+
+    fn get_possible_plays(&self) -> Vec<(usize, usize, board::Cell)> {
+        self.0.get_possible_marked_plays()
+    }
+
+    fn play_move(&mut self, (row_index, col_index, mark): (usize, usize, board::Cell)) {
+        self.0.play_mark(mark, row_index, col_index).unwrap();
+    }
+
+    fn get_distinct_plays(&self) -> Vec<(usize, usize, board::Cell)> {
+        let mut canonical_games: Vec<game::Game> = Vec::new();
+
+        self.0
+            .get_possible_marked_plays()
+            .into_iter()
+            .filter(|&(row_index, col_index, mark)| {
+                let (canonical_game, _) = self
+                    .0
+                    .get_played_mark(mark, row_index, col_index)
+                    .unwrap()
+                    .get_canonical();
+                if canonical_games.contains(&canonical_game) {
+                    return false;
+                }
+
+                canonical_games.push(canonical_game);
+                true
+            })
+            .collect()
+    }
+
+    fn get_turn(&self) -> game::GameTurn {
+        self.0.get_turn()
+    }
+
+    fn get_outcome(&self) -> Option<GameOutcome<game::GameTurn>> {
+        self.0.get_outcome()
+    }
+
+    fn is_over(&self) -> bool {
+        self.0.is_over()
+    }
+}
+
+impl fmt::Display for WildGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl MCTSGame for ultimate::Game {
+    type Move = (usize, usize);
+    type Player = game::GameTurn;
+
+    fn get_possible_plays(&self) -> Vec<(usize, usize)> {
+        ultimate::Game::get_possible_plays(self)
+    }
+
+    fn play_move(&mut self, (row_index, col_index): (usize, usize)) {
+        self.play(row_index, col_index).unwrap();
+    }
+
+    fn get_turn(&self) -> game::GameTurn {
+        ultimate::Game::get_turn(self)
+    }
+
+    fn get_outcome(&self) -> Option<GameOutcome<game::GameTurn>> {
+        match self.get_state() {
+            game::GameState::Ongoing => None,
+            game::GameState::XWon => Some(GameOutcome::Won(game::GameTurn::TurnX)),
+            game::GameState::OWon => Some(GameOutcome::Won(game::GameTurn::TurnO)),
+            game::GameState::Tie => Some(GameOutcome::Tie),
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        ultimate::Game::is_over(self)
+    }
+}
+
+impl MCTSGame for gomoku::Game {
+    type Move = gomoku::Move;
+    type Player = gomoku::Player;
+
+    fn get_possible_plays(&self) -> Vec<gomoku::Move> {
+        gomoku::Game::get_possible_plays(self)
+    }
+
+    fn play_move(&mut self, game_move: gomoku::Move) {
+        self.play(game_move).unwrap();
+    }
+
+    fn get_turn(&self) -> gomoku::Player {
+        gomoku::Game::get_turn(self)
+    }
+
+    fn get_outcome(&self) -> Option<GameOutcome<gomoku::Player>> {
+        match self.get_state() {
+            gomoku::GameState::Ongoing => None,
+            gomoku::GameState::FirstWon => Some(GameOutcome::Won(gomoku::Player::First)),
+            gomoku::GameState::SecondWon => Some(GameOutcome::Won(gomoku::Player::Second)),
+            gomoku::GameState::Tie => Some(GameOutcome::Tie),
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        gomoku::Game::is_over(self)
+    }
+}
+
+impl MCTSGame for connect_four_game::Game {
+    type Move = usize;
+    type Player = connect_four_game::GameTurn;
+
+    fn get_possible_plays(&self) -> Vec<usize> {
+        connect_four_game::Game::get_possible_plays(self)
+    }
+
+    fn play_move(&mut self, col_index: usize) {
+        self.play(col_index).unwrap();
+    }
+
+    fn get_distinct_plays(&self) -> Vec<usize> {
+        let mut kept_games: Vec<connect_four_game::Game> = Vec::new();
+
+        connect_four_game::Game::get_possible_plays(self)
+            .into_iter()
+            .filter(|&col_index| {
+                let played_game = self.get_played(col_index).unwrap();
+                let reflected_game = played_game.get_reflected();
+                if kept_games.contains(&reflected_game) {
+                    return false;
+                }
+
+                kept_games.push(played_game);
+                true
+            })
+            .collect()
+    }
+
+    fn get_turn(&self) -> connect_four_game::GameTurn {
+        connect_four_game::Game::get_turn(self)
+    }
+
+    fn get_outcome(&self) -> Option<GameOutcome<connect_four_game::GameTurn>> {
+        match self.get_state() {
+            connect_four_game::GameState::Ongoing => None,
+            connect_four_game::GameState::RedWon => {
+                Some(GameOutcome::Won(connect_four_game::GameTurn::TurnRed))
+            }
+            connect_four_game::GameState::YellowWon => {
+                Some(GameOutcome::Won(connect_four_game::GameTurn::TurnYellow))
+            }
+            connect_four_game::GameState::Tie => Some(GameOutcome::Tie),
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        connect_four_game::Game::is_over(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tic_tac_toe::board::Cell::{O, X};
+
+    #[test]
+    fn test_tic_tac_toe_outcome() {
+        let mut game = game::Game::new();
+        assert_eq!(game.get_outcome(), None);
+
+        // X wins along the first row
+        for game_move in [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+            game.play_move(game_move);
+        }
+
+        assert_eq!(
+            game.get_outcome(),
+            Some(GameOutcome::Won(game::GameTurn::TurnX))
+        );
+        assert!(MCTSGame::is_over(&game));
+        assert_eq!(MCTSGame::get_possible_plays(&game).len(), 0);
+
+        // Turn switches to the losing player after the final move
+        assert_eq!(MCTSGame::get_turn(&game), game::GameTurn::TurnO);
+    }
+
+    #[test]
+    fn test_tic_tac_toe_distinct_plays() {
+        // Corner, edge and centre openings
+        let mut game = game::Game::new();
+        assert_eq!(game.get_distinct_plays(), vec![(0, 0), (0, 1), (1, 1)]);
+
+        // After X takes the bottom edge, only the mirror across the middle column remains
+        game.play_move((2, 1));
+        assert_eq!(
+            game.get_distinct_plays(),
+            vec![(0, 0), (0, 1), (1, 0), (1, 1), (2, 0)]
+        );
+
+        // No symmetry is left once O plays off the middle column
+        game.play_move((0, 0));
+        assert_eq!(game.get_distinct_plays(), game.get_possible_plays());
+    }
+
+    #[test]
+    fn test_wild_tic_tac_toe_plays() {
+        // Every empty cell can take either mark
+        let mut game = WildGame(game::Game::with_rules(3, 3, 3, game::GameRules::Wild).unwrap());
+        assert_eq!(game.get_possible_plays().len(), 18);
+        assert_eq!(
+            game.get_distinct_plays(),
+            vec![
+                (0, 0, X),
+                (0, 0, O),
+                (0, 1, X),
+                (0, 1, O),
+                (1, 1, X),
+                (1, 1, O)
+            ]
+        );
+
+        // O completes the first row with the Os X placed there, and wins
+        for game_move in [(0, 0, O), (1, 1, X), (0, 1, O), (0, 2, O)] {
+            game.play_move(game_move);
+        }
+
+        assert_eq!(
+            game.get_outcome(),
+            Some(GameOutcome::Won(game::GameTurn::TurnO))
+        );
+        assert_eq!(game.get_turn(), game::GameTurn::TurnX);
+    }
+
+    #[test]
+    fn test_ultimate_tic_tac_toe_plays() {
+        let mut game = ultimate::Game::new();
+        assert_eq!(MCTSGame::get_possible_plays(&game).len(), 81);
+
+        // X plays the top left cell of the central sub-board, so O is sent to the top left sub-board
+        game.play_move((3, 3));
+        assert_eq!(
+            MCTSGame::get_possible_plays(&game),
+            vec![
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (1, 0),
+                (1, 1),
+                (1, 2),
+                (2, 0),
+                (2, 1),
+                (2, 2)
+            ]
+        );
+        assert_eq!(MCTSGame::get_turn(&game), game::GameTurn::TurnO);
+        assert_eq!(game.get_outcome(), None);
+    }
+
+    #[test]
+    fn test_gomoku_swap2_turns() {
+        let rules = gomoku::GameRules {
+            opening: gomoku::Opening::Swap2,
+            ..Default::default()
+        };
+        let mut game = gomoku::Game::with_rules(rules);
+
+        // The first player places the three opening stones in a row
+        for location in [(7, 7), (7, 8), (8, 8)] {
+            assert_eq!(MCTSGame::get_turn(&game), gomoku::Player::First);
+            game.play_move(gomoku::Move::Place(location.0, location.1));
+        }
+
+        assert_eq!(MCTSGame::get_turn(&game), gomoku::Player::Second);
+        assert_eq!(
+            MCTSGame::get_possible_plays(&game).len(),
+            2 + gomoku::BOARD_SIZE * gomoku::BOARD_SIZE - 3
+        );
+        assert_eq!(game.get_outcome(), None);
+    }
+
+    #[test]
+    fn test_connect_four_outcome() {
+        let mut game = connect_four_game::Game::new();
+        assert_eq!(game.get_outcome(), None);
+
+        // Red wins along the bottom row
+        for col_index in [0, 0, 1, 1, 2, 2, 3] {
+            game.play_move(col_index);
+        }
+
+        assert_eq!(
+            game.get_outcome(),
+            Some(GameOutcome::Won(connect_four_game::GameTurn::TurnRed))
+        );
+        assert_eq!(MCTSGame::get_possible_plays(&game).len(), 0);
+        assert_eq!(
+            MCTSGame::get_turn(&game),
+            connect_four_game::GameTurn::TurnYellow
+        );
+    }
+
+    #[test]
+    fn test_connect_four_distinct_plays() {
+        // The empty board is symmetric, so columns mirrored across the middle one are merged
+        let mut game = connect_four_game::Game::new();
+        assert_eq!(game.get_distinct_plays(), vec![0, 1, 2, 3]);
+
+        game.play_move(0);
+        assert_eq!(game.get_distinct_plays(), game.get_possible_plays());
+    }
+
+    #[test]
+    #[should_panic(expected = "WildGame")]
+    fn test_wild_tic_tac_toe_needs_wild_game() {
+        // Searching a wild game with plain moves would only ever place the player's own mark
+        let game = game::Game::with_rules(3, 3, 3, game::GameRules::Wild).unwrap();
+        MCTSGame::get_possible_plays(&game);
+    }
+}
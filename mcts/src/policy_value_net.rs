@@ -0,0 +1,540 @@
+//! Contains a small policy/value network for Tic-Tac-Toe, written from scratch so that it runs on the CPU without
+//! any machine learning framework
+//!
+//! The network is an MLP (Multi-Layer Perceptron) with ReLU hidden layers. It reads the board from the point of
+//! view of the player to move, with one input per cell for their own marks and one per cell for the opponent's.
+//! Its output layer holds one logit per cell, turned into move priors by a softmax over the empty cells, and one
+//! value logit, turned into the value of the position for the player to move by a sigmoid. Once trained (see
+//! `self_play`), the network is used as the evaluator of a PUCT search.
+//!
+//! ## Examples
+//!
+//! ```
+//! use mcts::{mcts_core, policy_value_net, search_config};
+//! use rand::SeedableRng;
+//! use std::sync;
+//! use tic_tac_toe::game;
+//!
+//! let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+//! let net = policy_value_net::PolicyValueNet::new(&[32], &mut rng);
+//!
+//! let config = search_config::SearchConfig {
+//!     evaluator: Some(sync::Arc::new(net)),
+//!     budget: search_config::SearchBudget::Iterations(50),
+//!     ..Default::default()
+//! };
+//!
+//! let mut tree = mcts_core::MCTree::new(&game::Game::new());
+//! assert!(tree.think_about_best_move(&config).is_some());
+//! ```
+
+use crate::evaluator::{Evaluation, Evaluator, RolloutEvaluator};
+use crate::search_config::SearchConfig;
+use rand::{Rng, RngCore};
+use std::fs;
+use std::io;
+use std::path;
+use tic_tac_toe::{board, game};
+
+/// Number of cells on the board
+pub const CELL_COUNT: usize = 9;
+
+/// Number of inputs of the network: own marks, then opponent marks, for every cell
+pub const INPUT_SIZE: usize = 2 * CELL_COUNT;
+
+/// Number of outputs of the network: a policy logit for every cell, then the value logit
+const OUTPUT_SIZE: usize = CELL_COUNT + 1;
+
+/// Represents a position the network learns from. Includes
+/// - cells of the board, row by row
+/// - turn of the player to move
+/// - share of the search visits that went to every cell, row by row
+/// - reward the player to move eventually got, between 0 (loss) and 1 (win)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingSample {
+    pub cells: [board::Cell; CELL_COUNT],
+    pub turn: game::GameTurn,
+    pub visit_shares: [f64; CELL_COUNT],
+    pub outcome: f64,
+}
+
+/// Represents a fully connected layer, with weights stored output by output
+#[derive(Debug, Clone, PartialEq)]
+struct Layer {
+    input_size: usize,
+    output_size: usize,
+    weights: Vec<f64>,
+    biases: Vec<f64>,
+}
+
+impl Layer {
+    /// Returns a layer with He-uniform random weights and zero biases
+    fn new<R: Rng + ?Sized>(input_size: usize, output_size: usize, rng: &mut R) -> Self {
+        let bound = (6.0 / input_size as f64).sqrt();
+
+        Layer {
+            input_size,
+            output_size,
+            weights: (0..input_size * output_size)
+                .map(|_| rng.gen_range(-bound..bound))
+                .collect(),
+            biases: vec![0.0; output_size],
+        }
+    }
+
+    /// Computes the outputs of the layer for `inputs`, before any activation
+    fn forward(&self, inputs: &[f64]) -> Vec<f64> {
+        (0..self.output_size)
+            .map(|output| {
+                let weights =
+                    &self.weights[output * self.input_size..(output + 1) * self.input_size];
+                self.biases[output]
+                    + weights
+                        .iter()
+                        .zip(inputs)
+                        .map(|(weight, input)| weight * input)
+                        .sum::<f64>()
+            })
+            .collect()
+    }
+}
+
+/// Represents a policy/value MLP for Tic-Tac-Toe
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyValueNet {
+    layers: Vec<Layer>,
+}
+
+/// Returns a boolean indicating whether the network can read `game`: a game following the standard rules on a
+/// 3x3 board with 3 in a row to win
+pub fn is_supported(game: &game::Game) -> bool {
+    game.get_board().get_width() == 3
+        && game.get_board().get_height() == 3
+        && game.get_win_length() == 3
+        && game.get_rules() == game::GameRules::Standard
+}
+
+/// Gets the cells of the board of `game`, row by row. Panics if the network does not support `game` (see
+/// `is_supported`)
+pub fn get_cells(game: &game::Game) -> [board::Cell; CELL_COUNT] {
+    assert!(
+        is_supported(game),
+        "The policy/value network only supports standard 3x3 Tic-Tac-Toe."
+    );
+    let mut cells = [board::Cell::Empty; CELL_COUNT];
+
+    for (cell_index, cell) in cells.iter_mut().enumerate() {
+        *cell = game
+            .get_board()
+            .get_cell(cell_index / 3, cell_index % 3)
+            .unwrap();
+    }
+
+    cells
+}
+
+/// Encodes `cells` as network inputs from the point of view of the player whose turn is `turn`
+pub fn encode(cells: &[board::Cell; CELL_COUNT], turn: game::GameTurn) -> [f64; INPUT_SIZE] {
+    let (own_mark, opponent_mark) = match turn {
+        game::GameTurn::TurnX => (board::Cell::X, board::Cell::O),
+        game::GameTurn::TurnO => (board::Cell::O, board::Cell::X),
+    };
+    let mut inputs = [0.0; INPUT_SIZE];
+
+    for (cell_index, &cell) in cells.iter().enumerate() {
+        if cell == own_mark {
+            inputs[cell_index] = 1.0;
+        } else if cell == opponent_mark {
+            inputs[CELL_COUNT + cell_index] = 1.0;
+        }
+    }
+
+    inputs
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// Turns the policy logits in `outputs` into probabilities over the empty cells of `cells`. Other cells get 0
+fn masked_softmax(outputs: &[f64], cells: &[board::Cell; CELL_COUNT]) -> [f64; CELL_COUNT] {
+    let mut priors = [0.0; CELL_COUNT];
+    let max_logit = (0..CELL_COUNT)
+        .filter(|&cell_index| cells[cell_index] == board::Cell::Empty)
+        .map(|cell_index| outputs[cell_index])
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    let mut total = 0.0;
+    for cell_index in 0..CELL_COUNT {
+        if cells[cell_index] == board::Cell::Empty {
+            priors[cell_index] = (outputs[cell_index] - max_logit).exp();
+            total += priors[cell_index];
+        }
+    }
+
+    if total > 0.0 {
+        for prior in priors.iter_mut() {
+            *prior /= total;
+        }
+    }
+
+    priors
+}
+
+impl PolicyValueNet {
+    /// Returns a randomly initialized network with hidden layers of `hidden_sizes`
+    pub fn new<R: Rng + ?Sized>(hidden_sizes: &[usize], rng: &mut R) -> Self {
+        let mut sizes = vec![INPUT_SIZE];
+        sizes.extend_from_slice(hidden_sizes);
+        sizes.push(OUTPUT_SIZE);
+
+        PolicyValueNet {
+            layers: sizes
+                .windows(2)
+                .map(|window| Layer::new(window[0], window[1], rng))
+                .collect(),
+        }
+    }
+
+    /// Runs the network on `inputs`. Returns the inputs of every layer followed by the raw outputs of the network
+    fn forward(&self, inputs: &[f64]) -> Vec<Vec<f64>> {
+        let mut activations = vec![inputs.to_vec()];
+
+        for (layer_index, layer) in self.layers.iter().enumerate() {
+            let mut outputs = layer.forward(activations.last().unwrap());
+
+            if layer_index + 1 < self.layers.len() {
+                for output in outputs.iter_mut() {
+                    *output = output.max(0.0);
+                }
+            }
+
+            activations.push(outputs);
+        }
+
+        activations
+    }
+
+    /// Predicts the move priors of every cell, row by row, and the value for the player to move of the board
+    /// `cells` when it is the turn of `turn`
+    pub fn predict(
+        &self,
+        cells: &[board::Cell; CELL_COUNT],
+        turn: game::GameTurn,
+    ) -> ([f64; CELL_COUNT], f64) {
+        let activations = self.forward(&encode(cells, turn));
+        let outputs = activations.last().unwrap();
+
+        (masked_softmax(outputs, cells), sigmoid(outputs[CELL_COUNT]))
+    }
+
+    /// Performs one step of gradient descent on `batch`, minimizing the cross-entropy between the priors and the
+    /// visit shares plus the binary cross-entropy between the value and the outcome, with L2 regularization of
+    /// strength `weight_decay`. Returns the average loss over the batch before the step
+    pub fn train_batch(
+        &mut self,
+        batch: &[TrainingSample],
+        learning_rate: f64,
+        weight_decay: f64,
+    ) -> f64 {
+        let mut weight_gradients: Vec<Vec<f64>> = self
+            .layers
+            .iter()
+            .map(|layer| vec![0.0; layer.weights.len()])
+            .collect();
+        let mut bias_gradients: Vec<Vec<f64>> = self
+            .layers
+            .iter()
+            .map(|layer| vec![0.0; layer.biases.len()])
+            .collect();
+        let mut total_loss = 0.0;
+
+        for sample in batch {
+            let activations = self.forward(&encode(&sample.cells, sample.turn));
+            let outputs = activations.last().unwrap();
+            let priors = masked_softmax(outputs, &sample.cells);
+            let value = sigmoid(outputs[CELL_COUNT]);
+
+            // Gradients of the loss with respect to the raw outputs
+            let mut deltas = vec![0.0; OUTPUT_SIZE];
+            for cell_index in 0..CELL_COUNT {
+                if sample.cells[cell_index] == board::Cell::Empty {
+                    deltas[cell_index] = priors[cell_index] - sample.visit_shares[cell_index];
+                    if sample.visit_shares[cell_index] > 0.0 {
+                        total_loss -=
+                            sample.visit_shares[cell_index] * priors[cell_index].max(1e-12).ln();
+                    }
+                }
+            }
+            deltas[CELL_COUNT] = value - sample.outcome;
+            total_loss -= sample.outcome * value.max(1e-12).ln()
+                + (1.0 - sample.outcome) * (1.0 - value).max(1e-12).ln();
+
+            for layer_index in (0..self.layers.len()).rev() {
+                let layer = &self.layers[layer_index];
+                let inputs = &activations[layer_index];
+
+                for output in 0..layer.output_size {
+                    bias_gradients[layer_index][output] += deltas[output];
+                    for input in 0..layer.input_size {
+                        weight_gradients[layer_index][output * layer.input_size + input] +=
+                            deltas[output] * inputs[input];
+                    }
+                }
+
+                if layer_index > 0 {
+                    deltas = (0..layer.input_size)
+                        .map(|input| {
+                            // Inputs of hidden layers went through a ReLU
+                            if inputs[input] <= 0.0 {
+                                return 0.0;
+                            }
+
+                            (0..layer.output_size)
+                                .map(|output| {
+                                    layer.weights[output * layer.input_size + input]
+                                        * deltas[output]
+                                })
+                                .sum()
+                        })
+                        .collect();
+                }
+            }
+        }
+
+        let scale = learning_rate / batch.len().max(1) as f64;
+        for (layer_index, layer) in self.layers.iter_mut().enumerate() {
+            for (weight, gradient) in layer.weights.iter_mut().zip(&weight_gradients[layer_index]) {
+                *weight -= scale * gradient + learning_rate * weight_decay * *weight;
+            }
+            for (bias, gradient) in layer.biases.iter_mut().zip(&bias_gradients[layer_index]) {
+                *bias -= scale * gradient;
+            }
+        }
+
+        total_loss / batch.len().max(1) as f64
+    }
+
+    /// Saves the weights of the network to the text file at `path`. The first line lists the layer sizes, and
+    /// every layer follows with a line of weights and a line of biases
+    pub fn save(&self, path: &path::Path) -> io::Result<()> {
+        let mut sizes = vec![INPUT_SIZE.to_string()];
+        sizes.extend(
+            self.layers
+                .iter()
+                .map(|layer| layer.output_size.to_string()),
+        );
+
+        let mut lines = vec![sizes.join(" ")];
+        for layer in self.layers.iter() {
+            for values in [&layer.weights, &layer.biases] {
+                let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+                lines.push(values.join(" "));
+            }
+        }
+
+        fs::write(path, lines.join("\n") + "\n")
+    }
+
+    /// Loads a network saved with `save` from the text file at `path`. Returns an `Err` if the file cannot be read
+    /// or does not hold a network for Tic-Tac-Toe
+    pub fn load(path: &path::Path) -> io::Result<Self> {
+        let invalid_data =
+            |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let contents = fs::read_to_string(path)?;
+        let mut lines = contents.lines();
+
+        let sizes: Vec<usize> = lines
+            .next()
+            .ok_or_else(|| invalid_data("Missing layer sizes."))?
+            .split_whitespace()
+            .map(|size| {
+                size.parse()
+                    .map_err(|_| invalid_data("Invalid layer size."))
+            })
+            .collect::<io::Result<_>>()?;
+
+        if sizes.len() < 2 || sizes[0] != INPUT_SIZE || sizes[sizes.len() - 1] != OUTPUT_SIZE {
+            return Err(invalid_data("Layer sizes do not fit Tic-Tac-Toe."));
+        }
+
+        let mut parse_values = |expected_count: usize| -> io::Result<Vec<f64>> {
+            let values: Vec<f64> = lines
+                .next()
+                .ok_or_else(|| invalid_data("Missing layer values."))?
+                .split_whitespace()
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| invalid_data("Invalid layer value."))
+                })
+                .collect::<io::Result<_>>()?;
+
+            if values.len() != expected_count {
+                return Err(invalid_data("Wrong number of layer values."));
+            }
+
+            Ok(values)
+        };
+
+        let mut layers = Vec::new();
+        for window in sizes.windows(2) {
+            let (input_size, output_size) = (window[0], window[1]);
+            layers.push(Layer {
+                input_size,
+                output_size,
+                weights: parse_values(input_size * output_size)?,
+                biases: parse_values(output_size)?,
+            });
+        }
+
+        Ok(PolicyValueNet { layers })
+    }
+}
+
+/// Games the network does not support (see `is_supported`) are evaluated with a playout instead, as by
+/// `RolloutEvaluator`
+impl Evaluator<game::Game> for PolicyValueNet {
+    fn evaluate(
+        &self,
+        game: &game::Game,
+        config: &SearchConfig<game::Game>,
+        rng: &mut dyn RngCore,
+    ) -> Evaluation<(usize, usize)> {
+        if !is_supported(game) {
+            return RolloutEvaluator.evaluate(game, config, rng);
+        }
+
+        let (cell_priors, value) = self.predict(&get_cells(game), game.get_turn());
+
+        let priors = game
+            .get_possible_plays()
+            .into_iter()
+            .map(|(row_index, col_index)| {
+                (
+                    (row_index, col_index),
+                    cell_priors[row_index * 3 + col_index],
+                )
+            })
+            .collect();
+
+        Evaluation { priors, value }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs, SeedableRng};
+
+    fn sample_after(moves: &[(usize, usize)], target_cell: usize, outcome: f64) -> TrainingSample {
+        let mut game = game::Game::new();
+        for &(row_index, col_index) in moves {
+            game.play(row_index, col_index).unwrap();
+        }
+
+        let mut visit_shares = [0.0; CELL_COUNT];
+        visit_shares[target_cell] = 1.0;
+
+        TrainingSample {
+            cells: get_cells(&game),
+            turn: game.get_turn(),
+            visit_shares,
+            outcome,
+        }
+    }
+
+    #[test]
+    fn test_encode_player_to_move() {
+        let mut cells = [board::Cell::Empty; CELL_COUNT];
+        cells[0] = board::Cell::X;
+        cells[4] = board::Cell::O;
+
+        let inputs = encode(&cells, game::GameTurn::TurnO);
+        assert!((inputs[4] - 1.0).abs() < 1e-7);
+        assert!((inputs[CELL_COUNT] - 1.0).abs() < 1e-7);
+        assert!((inputs.iter().sum::<f64>() - 2.0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_predict_masks_occupied_cells() {
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let net = PolicyValueNet::new(&[16], &mut rng);
+        let sample = sample_after(&[(0, 0), (1, 1)], 2, 0.5);
+
+        let (priors, value) = net.predict(&sample.cells, sample.turn);
+        assert!((priors[0] - 0.0).abs() < 1e-7);
+        assert!((priors[4] - 0.0).abs() < 1e-7);
+        assert!((priors.iter().sum::<f64>() - 1.0).abs() < 1e-7);
+        assert!(value > 0.0 && value < 1.0);
+    }
+
+    #[test]
+    fn test_train_batch_fits_samples() {
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let mut net = PolicyValueNet::new(&[32], &mut rng);
+        let batch = vec![
+            sample_after(&[], 4, 0.5),
+            sample_after(&[(0, 0), (1, 0), (0, 1), (1, 1)], 2, 1.0),
+        ];
+
+        let initial_loss = net.train_batch(&batch, 0.1, 0.0);
+        let mut loss = initial_loss;
+        for _ in 0..500 {
+            loss = net.train_batch(&batch, 0.1, 0.0);
+        }
+
+        assert!(loss < initial_loss / 2.0);
+
+        let (priors, value) = net.predict(&batch[1].cells, batch[1].turn);
+        assert!(priors[2] > 0.8);
+        assert!(value > 0.8);
+    }
+
+    #[test]
+    fn test_evaluate_unsupported_games() {
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let net = PolicyValueNet::new(&[16], &mut rng);
+        let config = crate::search_config::SearchConfig {
+            evaluator: Some(std::sync::Arc::new(net)),
+            budget: crate::search_config::SearchBudget::Iterations(50),
+            ..Default::default()
+        };
+
+        // Larger boards and other rules are searched with playouts instead of panicking
+        for game in [
+            game::Game::with_size(4, 4, 3).unwrap(),
+            game::Game::with_rules(3, 3, 3, game::GameRules::Misere).unwrap(),
+        ] {
+            assert!(!is_supported(&game));
+            let mut tree = crate::mcts_core::MCTree::new(&game);
+            assert!(tree.think_about_best_move(&config).is_some());
+        }
+
+        assert!(is_supported(&game::Game::new()));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let net = PolicyValueNet::new(&[8, 4], &mut rng);
+        let path = std::env::temp_dir().join("mcts_test_save_and_load_net.txt");
+
+        net.save(&path).unwrap();
+        let loaded = PolicyValueNet::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, net);
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_file() {
+        let path = std::env::temp_dir().join("mcts_test_load_rejects_invalid_net.txt");
+        fs::write(&path, "18 4 10\n1 2 3\n").unwrap();
+
+        let result = PolicyValueNet::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,279 @@
+//! Contains the policies that pick moves during MCTS (Monte Carlo Tree Search) playouts
+//!
+//! A playout plays a game from a newly added node until the game is over. The policy picking the moves of
+//! a playout decides how well its result predicts the true value of the node: uniformly random playouts are
+//! cheap, but misjudge lines where one player is forced to respond.
+//!
+//! ## Examples
+//!
+//! ```
+//! use mcts::{mcts_core, rollout_policy, search_config};
+//! use std::sync;
+//! use tic_tac_toe::game;
+//!
+//! let config = search_config::SearchConfig {
+//!     rollout_policy: sync::Arc::new(rollout_policy::TacticalRollout),
+//!     budget: search_config::SearchBudget::Iterations(100),
+//!     ..Default::default()
+//! };
+//!
+//! let mut tree = mcts_core::MCTree::new(&game::Game::new());
+//! assert!(tree.think_about_best_move(&config).is_some());
+//! ```
+
+use crate::mcts_game::{GameOutcome, MCTSGame};
+use rand::{Rng, RngCore};
+use tic_tac_toe::game;
+
+/// Represents a policy picking the moves of a playout
+pub trait RolloutPolicy<G: MCTSGame>: Send + Sync {
+    /// Picks the move to play in `game` among `possible_plays`, which is never empty
+    fn choose_move(&self, game: &G, possible_plays: &[G::Move], rng: &mut dyn RngCore) -> G::Move;
+}
+
+/// Picks every legal move with the same probability
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UniformRollout;
+
+impl<G: MCTSGame> RolloutPolicy<G> for UniformRollout {
+    fn choose_move(&self, _game: &G, possible_plays: &[G::Move], rng: &mut dyn RngCore) -> G::Move {
+        possible_plays[rng.gen_range(0..possible_plays.len())]
+    }
+}
+
+/// Plays a move that wins on the spot if there is one. Otherwise, avoids moves that lose on the spot and moves
+/// after which the opponent can win on the spot, which blocks the opponent whenever a block exists. Picks
+/// uniformly at random among the remaining moves. Works for any game, at the cost of looking two moves ahead on
+/// every playout move
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TacticalRollout;
+
+impl TacticalRollout {
+    /// Returns a boolean indicating whether the player to move in `game` can win with a single move
+    fn has_winning_move<G: MCTSGame>(game: &G) -> bool {
+        let player = game.get_turn();
+
+        game.get_possible_plays().into_iter().any(|game_move| {
+            game.get_played_move(game_move).get_outcome() == Some(GameOutcome::Won(player))
+        })
+    }
+}
+
+impl<G: MCTSGame> RolloutPolicy<G> for TacticalRollout {
+    fn choose_move(&self, game: &G, possible_plays: &[G::Move], rng: &mut dyn RngCore) -> G::Move {
+        let player = game.get_turn();
+        let mut safe_plays = Vec::with_capacity(possible_plays.len());
+
+        for &game_move in possible_plays {
+            let played_game = game.get_played_move(game_move);
+
+            match played_game.get_outcome() {
+                Some(GameOutcome::Won(winner)) if winner == player => return game_move,
+                // Under misère rules, a move ending the game can lose it
+                Some(GameOutcome::Won(_)) => {}
+                Some(GameOutcome::Tie) => safe_plays.push(game_move),
+                None => {
+                    if !Self::has_winning_move(&played_game) {
+                        safe_plays.push(game_move);
+                    }
+                }
+            }
+        }
+
+        // Every move loses on the spot, so any of them will do
+        if safe_plays.is_empty() {
+            return UniformRollout.choose_move(game, possible_plays, rng);
+        }
+
+        safe_plays[rng.gen_range(0..safe_plays.len())]
+    }
+}
+
+/// Picks Tic-Tac-Toe moves at random with a probability proportional to a weight per cell type, so that the
+/// centre and corners, which lie on the most winning lines, are played first. On boards larger than 3x3, every
+/// cell off the border counts as a centre cell
+#[derive(Debug, Clone, Copy)]
+pub struct WeightedRollout {
+    pub center_weight: f64,
+    pub corner_weight: f64,
+    pub edge_weight: f64,
+}
+
+impl Default for WeightedRollout {
+    /// Weighs every cell by the number of winning lines going through it on a 3x3 board
+    fn default() -> Self {
+        WeightedRollout {
+            center_weight: 4.0,
+            corner_weight: 3.0,
+            edge_weight: 2.0,
+        }
+    }
+}
+
+impl WeightedRollout {
+    /// Gets the weight of the cell at location (`row_index`, `col_index`) of a board with `height` rows and
+    /// `width` columns
+    fn get_weight(&self, row_index: usize, col_index: usize, height: usize, width: usize) -> f64 {
+        let on_top_or_bottom = row_index == 0 || row_index + 1 == height;
+        let on_left_or_right = col_index == 0 || col_index + 1 == width;
+
+        match (on_top_or_bottom, on_left_or_right) {
+            (false, false) => self.center_weight,
+            (true, true) => self.corner_weight,
+            _ => self.edge_weight,
+        }
+    }
+}
+
+impl RolloutPolicy<game::Game> for WeightedRollout {
+    fn choose_move(
+        &self,
+        game: &game::Game,
+        possible_plays: &[(usize, usize)],
+        rng: &mut dyn RngCore,
+    ) -> (usize, usize) {
+        let (height, width) = (game.get_board().get_height(), game.get_board().get_width());
+        let weight = |row_index, col_index| self.get_weight(row_index, col_index, height, width);
+
+        let total_weight: f64 = possible_plays
+            .iter()
+            .map(|&(row_index, col_index)| weight(row_index, col_index))
+            .sum();
+
+        let mut target = rng.gen_range(0.0..total_weight);
+
+        for &(row_index, col_index) in possible_plays {
+            target -= weight(row_index, col_index);
+            if target < 0.0 {
+                return (row_index, col_index);
+            }
+        }
+
+        // Floating point rounding can leave a tiny remainder past the last cell
+        *possible_plays.last().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs, SeedableRng};
+
+    /// Plays `moves` from an empty board
+    fn game_after(moves: &[(usize, usize)]) -> game::Game {
+        let mut game = game::Game::new();
+        for &(row_index, col_index) in moves {
+            game.play(row_index, col_index).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_uniform_rollout_legal() {
+        let game = game_after(&[(0, 0), (1, 1)]);
+        let possible_plays = game.get_possible_plays();
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+
+        for _ in 0..50 {
+            let game_move = UniformRollout.choose_move(&game, &possible_plays, &mut rng);
+            assert!(possible_plays.contains(&game_move));
+        }
+    }
+
+    #[test]
+    fn test_tactical_rollout_wins() {
+        // X can complete the first row at (0, 2), and O threatens the second row at (1, 2)
+        let game = game_after(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        let possible_plays = game.get_possible_plays();
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+
+        for _ in 0..20 {
+            assert_eq!(
+                TacticalRollout.choose_move(&game, &possible_plays, &mut rng),
+                (0, 2)
+            );
+        }
+    }
+
+    #[test]
+    fn test_tactical_rollout_blocks() {
+        // O threatens the second row at (1, 2), and X has no win of its own
+        let game = game_after(&[(0, 0), (1, 0), (2, 2), (1, 1)]);
+        let possible_plays = game.get_possible_plays();
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+
+        for _ in 0..20 {
+            assert_eq!(
+                TacticalRollout.choose_move(&game, &possible_plays, &mut rng),
+                (1, 2)
+            );
+        }
+    }
+
+    #[test]
+    fn test_tactical_rollout_lost_position() {
+        // O threatens both (1, 2) and (2, 1), so every move of X loses and any legal move is returned
+        let game = game_after(&[(0, 0), (1, 1), (0, 2), (0, 1), (2, 0), (1, 0)]);
+        let possible_plays = game.get_possible_plays();
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+
+        let game_move = TacticalRollout.choose_move(&game, &possible_plays, &mut rng);
+        assert!(possible_plays.contains(&game_move));
+    }
+
+    #[test]
+    fn test_tactical_rollout_avoids_misere_loss() {
+        // Under misère rules, X loses by completing the first row at (0, 2)
+        let mut game = game::Game::with_rules(3, 3, 3, game::GameRules::Misere).unwrap();
+        for (row_index, col_index) in [(0, 0), (1, 1), (0, 1), (2, 2)] {
+            game.play(row_index, col_index).unwrap();
+        }
+        let possible_plays = game.get_possible_plays();
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+
+        for _ in 0..20 {
+            assert_ne!(
+                TacticalRollout.choose_move(&game, &possible_plays, &mut rng),
+                (0, 2)
+            );
+        }
+    }
+
+    #[test]
+    fn test_weighted_rollout_prefers_center() {
+        let game = game::Game::new();
+        let possible_plays = game.get_possible_plays();
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let policy = WeightedRollout::default();
+
+        let mut counts = [[0; 3]; 3];
+        for _ in 0..6000 {
+            let (row_index, col_index) = policy.choose_move(&game, &possible_plays, &mut rng);
+            counts[row_index][col_index] += 1;
+        }
+
+        // Expected counts are 6000 * weight / 28: ~857 for the centre, ~643 per corner, ~429 per edge
+        assert!(counts[1][1] > counts[0][0]);
+        assert!(counts[0][0] > counts[0][1]);
+        assert!(counts[2][2] > counts[2][1]);
+    }
+
+    #[test]
+    fn test_weighted_rollout_only_edge_left() {
+        let policy = WeightedRollout::default();
+        let game = game::Game::new();
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+
+        assert_eq!(policy.choose_move(&game, &[(0, 1)], &mut rng), (0, 1));
+    }
+
+    #[test]
+    fn test_weighted_rollout_weights_on_larger_board() {
+        let policy = WeightedRollout::default();
+
+        assert!((policy.get_weight(0, 4, 4, 5) - 3.0).abs() < 1e-7);
+        assert!((policy.get_weight(0, 2, 4, 5) - 2.0).abs() < 1e-7);
+        assert!((policy.get_weight(3, 0, 4, 5) - 3.0).abs() < 1e-7);
+        assert!((policy.get_weight(2, 3, 4, 5) - 4.0).abs() < 1e-7);
+    }
+}
@@ -0,0 +1,242 @@
+//! Contains the parameters that control an MCTS (Monte Carlo Tree Search) run
+//!
+//! ## Examples
+//!
+//! ```
+//! use mcts::{mcts_core, search_config};
+//! use std::time;
+//! use tic_tac_toe::game;
+//!
+//! let config = search_config::SearchConfig {
+//!     exploration_constant: 1.0,
+//!     budget: search_config::SearchBudget::Time(time::Duration::from_millis(50)),
+//!     seed: Some(42),
+//!     ..Default::default()
+//! };
+//!
+//! let mut tree = mcts_core::MCTree::new(&game::Game::new());
+//! assert!(tree.think_about_best_move(&config).is_some());
+//! ```
+
+use crate::evaluator::Evaluator;
+use crate::mcts_game::MCTSGame;
+use crate::rollout_policy::{RolloutPolicy, UniformRollout};
+use crate::tree_policy::TreePolicy;
+use rand::{rngs, SeedableRng};
+use std::sync::{self, atomic};
+use std::time;
+
+/// Represents how much work a search is allowed to do before responding with a move
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchBudget {
+    /// Run a fixed number of MCTS iterations
+    Iterations(u32),
+    /// Keep running MCTS iterations until the given wall-clock time has elapsed
+    Time(time::Duration),
+}
+
+/// Represents the rule used to pick the move to play once the search is over. Children that were never
+/// visited are only picked when no child has been visited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FinalMovePolicy {
+    /// Pick the child with the highest win rate
+    #[default]
+    MaxChild,
+    /// Pick the child with the most visits
+    RobustChild,
+    /// Pick the child with both the highest win rate and the most visits. When no child has both, the
+    /// robust child is picked instead
+    MaxRobustChild,
+    /// Pick the child with the highest lower confidence bound on its win rate, using the exploration constant
+    SecureChild,
+}
+
+/// Represents how a search spreads its work across threads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Parallelism {
+    /// Search a single tree on the calling thread
+    #[default]
+    Sequential,
+    /// Search `threads` independent trees at once, each seeded differently, and merge the visits and wins of
+    /// their root children before picking the move. Every tree gets the full budget and limits
+    RootParallel { threads: usize },
+    /// Search a single shared tree on `threads` threads at once, spreading them over different branches with
+    /// virtual losses. The budget is shared by all threads. Only supported by `shared_tree::SharedTree`
+    TreeParallel { threads: usize },
+}
+
+/// Represents how a leaf of the search tree is expanded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExpansionMode {
+    /// Add children for all possible moves of the leaf at once, and run a playout from each of them. The amount
+    /// of work done by an iteration depends on the number of moves at the leaf
+    #[default]
+    All,
+    /// Add a child for a single untried move of the leaf, picked at random, and run one playout from it. A leaf
+    /// with fewer than `threshold` visits is not expanded, and the playout starts from the leaf itself. Every
+    /// iteration runs exactly one playout
+    Single { threshold: u32 },
+}
+
+/// Represents limits that stop a search before its budget is used up. The search stops as soon as any
+/// limit is hit and responds with the best move found so far. Limits are checked between iterations.
+/// Includes
+/// - wall-clock instant by which the search must stop
+/// - maximum number of nodes in the search tree (one final expansion may go slightly beyond it)
+/// - flag that any thread can set to stop the search
+#[derive(Debug, Clone, Default)]
+pub struct SearchLimits {
+    pub deadline: Option<time::Instant>,
+    pub max_nodes: Option<usize>,
+    pub stop_flag: Option<sync::Arc<atomic::AtomicBool>>,
+}
+
+impl SearchLimits {
+    /// Returns a boolean indicating whether any of the limits is hit given the current tree size
+    pub fn is_hit(&self, node_count: usize) -> bool {
+        if let Some(deadline) = self.deadline {
+            if time::Instant::now() >= deadline {
+                return true;
+            }
+        }
+
+        if let Some(max_nodes) = self.max_nodes {
+            if node_count >= max_nodes {
+                return true;
+            }
+        }
+
+        match &self.stop_flag {
+            Some(stop_flag) => stop_flag.load(atomic::Ordering::Relaxed),
+            None => false,
+        }
+    }
+}
+
+/// Represents the options of a `SearchConfig` that a search driver may not support. Drivers reject such options
+/// when checking a config
+#[derive(Debug, PartialEq, Eq)]
+pub enum SearchConfigError {
+    UnsupportedExpansion,
+    UnsupportedParallelism,
+    UnsupportedRave,
+    UnsupportedEvaluator,
+    UnsupportedSolver,
+}
+
+/// Represents the parameters of a search. Includes
+/// - exploration constant C weighing exploration against exploitation in UCT (Upper Confidence Bound for Trees)
+/// - reward given to both players when a playout ends in a tie
+/// - budget of the search
+/// - limits that may stop the search early
+/// - policy picking the final move
+/// - mode of expanding leaves
+/// - parallelism of the search
+/// - number of visits without a win that every virtual loss counts for, in tree-parallel search
+/// - RAVE (Rapid Action Value Estimation) equivalence parameter k, i.e. the number of visits at which a node's
+///   own win rate and its AMAF (All-Moves-As-First) win rate weigh roughly the same. None selects plain UCT
+/// - policy picking the moves of playouts
+/// - policy scoring children during selection. None picks PUCT with an evaluator and UCT without one
+/// - evaluator giving move priors and values to leaves. When set, selection follows PUCT (Predictor + Upper
+///   Confidence bounds applied to Trees) with the exploration constant as c_puct, leaves are evaluated instead
+///   of played out, and all children of a leaf are added at once
+/// - flag enabling MCTS-Solver, which proves nodes to be wins, losses or draws under perfect play, stops
+///   selecting proven losses and plays proven wins
+/// - flag merging children that lead to symmetric positions when expanding a node, keeping a single real move
+///   for each of them
+/// - seed of the random number generator used for playouts (None draws a fresh seed for every search).
+///   Runs with an iteration budget and the same seed are reproducible for a given version of `rand`
+///
+/// Every search driver rejects the options it does not support, as told by its `check_config`
+#[derive(Clone)]
+pub struct SearchConfig<G: MCTSGame> {
+    pub exploration_constant: f64,
+    pub draw_value: f64,
+    pub budget: SearchBudget,
+    pub limits: SearchLimits,
+    pub final_move_policy: FinalMovePolicy,
+    pub expansion: ExpansionMode,
+    pub parallelism: Parallelism,
+    pub virtual_loss: f64,
+    pub rave_equivalence: Option<f64>,
+    pub rollout_policy: sync::Arc<dyn RolloutPolicy<G>>,
+    pub tree_policy: Option<sync::Arc<dyn TreePolicy>>,
+    pub evaluator: Option<sync::Arc<dyn Evaluator<G>>>,
+    pub solver: bool,
+    pub merge_symmetries: bool,
+    pub seed: Option<u64>,
+}
+
+impl<G: MCTSGame> Default for SearchConfig<G> {
+    fn default() -> Self {
+        SearchConfig {
+            exploration_constant: 2.0_f64.sqrt(),
+            draw_value: 0.5,
+            budget: SearchBudget::Iterations(1000),
+            limits: SearchLimits::default(),
+            final_move_policy: FinalMovePolicy::default(),
+            expansion: ExpansionMode::default(),
+            parallelism: Parallelism::default(),
+            virtual_loss: 1.0,
+            rave_equivalence: None,
+            rollout_policy: sync::Arc::new(UniformRollout),
+            tree_policy: None,
+            evaluator: None,
+            solver: false,
+            merge_symmetries: false,
+            seed: None,
+        }
+    }
+}
+
+impl<G: MCTSGame> SearchConfig<G> {
+    /// Returns a random number generator seeded from `seed`, or from system entropy if `seed` is None
+    pub fn get_rng(&self) -> rngs::StdRng {
+        match self.seed {
+            Some(seed) => rngs::StdRng::seed_from_u64(seed),
+            None => rngs::StdRng::from_entropy(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_limits_never_hit() {
+        assert!(!SearchLimits::default().is_hit(usize::MAX));
+    }
+
+    #[test]
+    fn test_deadline_hit() {
+        let limits = SearchLimits {
+            deadline: Some(time::Instant::now()),
+            ..Default::default()
+        };
+        assert!(limits.is_hit(0));
+    }
+
+    #[test]
+    fn test_max_nodes_hit() {
+        let limits = SearchLimits {
+            max_nodes: Some(10),
+            ..Default::default()
+        };
+        assert!(!limits.is_hit(9));
+        assert!(limits.is_hit(10));
+    }
+
+    #[test]
+    fn test_stop_flag_hit() {
+        let stop_flag = sync::Arc::new(atomic::AtomicBool::new(false));
+        let limits = SearchLimits {
+            stop_flag: Some(sync::Arc::clone(&stop_flag)),
+            ..Default::default()
+        };
+        assert!(!limits.is_hit(0));
+
+        stop_flag.store(true, atomic::Ordering::Relaxed);
+        assert!(limits.is_hit(0));
+    }
+}
@@ -0,0 +1,72 @@
+//! Contains the report of an MCTS (Monte Carlo Tree Search) run, explaining the move it picked
+//!
+//! ## Examples
+//!
+//! ```
+//! use mcts::{mcts_core, search_config};
+//! use tic_tac_toe::game;
+//!
+//! let config = search_config::SearchConfig {
+//!     budget: search_config::SearchBudget::Iterations(200),
+//!     ..Default::default()
+//! };
+//!
+//! let mut tree = mcts_core::MCTree::new(&game::Game::new());
+//! let report = tree.search(&config);
+//!
+//! assert!(report.best_move.is_some());
+//! assert_eq!(report.principal_variation[0], report.best_move.unwrap());
+//! for move_stats in report.root_moves.iter() {
+//!     println!(
+//!         "{:?}: {} visits, mean value {:.3}",
+//!         move_stats.game_move, move_stats.visits, move_stats.mean_value
+//!     );
+//! }
+//! ```
+
+use crate::mcts_game::{GameOutcome, MCTSGame};
+use std::time;
+
+/// Represents the statistics of a move from the root, from the point of view of the player to move at the root.
+/// Includes
+/// - move
+/// - number of visits
+/// - mean reward over the visits, or 0 if the move was never visited
+/// - UCB (Upper Confidence Bound) of the mean reward: mean + C * sqrt(ln(N) / n), with the exploration constant
+///   of the search as C. Infinity if the move was never visited
+/// - outcome the move is proven to lead to, if known
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveStats<G: MCTSGame> {
+    pub game_move: G::Move,
+    pub visits: f64,
+    pub mean_value: f64,
+    pub ucb: f64,
+    pub proven: Option<GameOutcome<G::Player>>,
+}
+
+/// Represents the result of a search. Includes
+/// - move picked by the final move policy, or None if the game is already over
+/// - statistics of every move from the root, by decreasing number of visits
+/// - number of visits of the root
+/// - value of the root for the player to move there, between 0 (loss) and 1 (win), or 0 if never visited
+/// - principal variation: the picked move, followed by the most visited child at every level below it
+/// - number of iterations run
+/// - number of nodes in the tree
+/// - depth of the deepest node, the root being at depth 0
+/// - wall-clock time the search took
+///
+/// With root parallelism, root statistics are merged across all trees, node counts are summed, depths are the
+/// deepest among the trees, iterations are summed over all threads, and the principal variation below the picked
+/// move follows the tree kept by the caller
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchReport<G: MCTSGame> {
+    pub best_move: Option<G::Move>,
+    pub root_moves: Vec<MoveStats<G>>,
+    pub root_visits: f64,
+    pub root_value: f64,
+    pub principal_variation: Vec<G::Move>,
+    pub iterations: u32,
+    pub node_count: usize,
+    pub max_depth: usize,
+    pub elapsed: time::Duration,
+}
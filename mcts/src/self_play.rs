@@ -0,0 +1,412 @@
+//! Contains the self-play training loop of the Tic-Tac-Toe `PolicyValueNet`
+//!
+//! Every generation, the current network plays games against itself, picking moves with a PUCT search that uses
+//! the network as its evaluator. Every position of those games becomes a training sample pairing the position
+//! with the share of search visits every move got and with the outcome the player to move eventually got. The
+//! network then trains on random batches drawn from the most recent samples.
+//!
+//! ## Examples
+//!
+//! ```
+//! use mcts::{policy_value_net, self_play};
+//! use rand::SeedableRng;
+//!
+//! let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+//! let mut net = policy_value_net::PolicyValueNet::new(&[32], &mut rng);
+//!
+//! let config = self_play::TrainingConfig {
+//!     generations: 1,
+//!     games_per_generation: 2,
+//!     search_iterations: 20,
+//!     ..Default::default()
+//! };
+//!
+//! self_play::train(&mut net, &config, &mut rng, |report| {
+//!     println!("generation {}: loss {:.3}", report.generation, report.loss);
+//! })
+//! .unwrap();
+//! ```
+
+use crate::mcts_core::MCTree;
+use crate::mcts_game::{GameOutcome, MCTSGame};
+use crate::policy_value_net::{self, PolicyValueNet, TrainingSample, CELL_COUNT};
+use crate::search_config::{FinalMovePolicy, SearchBudget, SearchConfig};
+use rand::Rng;
+use std::collections;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path;
+use std::sync;
+use tic_tac_toe::{board, game};
+
+/// Represents the parameters of self-play training. Includes
+/// - number of generations, each made of self-play games followed by training
+/// - number of self-play games per generation
+/// - number of search iterations per move in self-play games
+/// - exploration constant c_puct of the search
+/// - number of moves at the start of every game that are sampled in proportion to search visits, rather than
+///   picked as the most visited move, so that games differ from each other
+/// - number of gradient descent steps per generation
+/// - number of samples per gradient descent step
+/// - learning rate of gradient descent
+/// - strength of the L2 regularization of the weights
+/// - maximum number of recent samples kept for training
+/// - path of a text file that every new sample is appended to, if any
+#[derive(Debug, Clone)]
+pub struct TrainingConfig {
+    pub generations: u32,
+    pub games_per_generation: u32,
+    pub search_iterations: u32,
+    pub exploration_constant: f64,
+    pub sampling_moves: u32,
+    pub training_steps: u32,
+    pub batch_size: usize,
+    pub learning_rate: f64,
+    pub weight_decay: f64,
+    pub replay_capacity: usize,
+    pub samples_path: Option<path::PathBuf>,
+}
+
+impl Default for TrainingConfig {
+    fn default() -> Self {
+        TrainingConfig {
+            generations: 50,
+            games_per_generation: 50,
+            search_iterations: 100,
+            exploration_constant: 1.5,
+            sampling_moves: 3,
+            training_steps: 200,
+            batch_size: 64,
+            learning_rate: 0.05,
+            weight_decay: 1e-4,
+            replay_capacity: 20000,
+            samples_path: None,
+        }
+    }
+}
+
+/// Represents the progress of training after a generation. Includes
+/// - index of the generation, starting at 0
+/// - number of samples available for training
+/// - average loss over the training steps of the generation
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationReport {
+    pub generation: u32,
+    pub sample_count: usize,
+    pub loss: f64,
+}
+
+/// Picks a move among `child_visits` with a probability proportional to its visits
+fn sample_by_visits<R: Rng + ?Sized>(
+    child_visits: &[((usize, usize), f64)],
+    rng: &mut R,
+) -> (usize, usize) {
+    let total_visits: f64 = child_visits.iter().map(|&(_, visits)| visits).sum();
+    let mut target = rng.gen_range(0.0..total_visits.max(f64::MIN_POSITIVE));
+
+    for &(game_move, visits) in child_visits {
+        target -= visits;
+        if target < 0.0 {
+            return game_move;
+        }
+    }
+
+    child_visits.last().unwrap().0
+}
+
+/// Plays one game of `net` against itself and returns a training sample for every position of the game
+pub fn play_self_play_game<R: Rng + ?Sized>(
+    net: &sync::Arc<PolicyValueNet>,
+    config: &TrainingConfig,
+    rng: &mut R,
+) -> Vec<TrainingSample> {
+    let search_config = SearchConfig {
+        evaluator: Some(net.clone()),
+        exploration_constant: config.exploration_constant,
+        budget: SearchBudget::Iterations(config.search_iterations),
+        final_move_policy: FinalMovePolicy::RobustChild,
+        ..Default::default()
+    };
+
+    let mut game = game::Game::new();
+    let mut tree = MCTree::new(&game);
+    let mut samples = Vec::new();
+    let mut move_count = 0;
+
+    while !game.is_over() {
+        let best_move = tree
+            .think_about_best_move_with_rng(&search_config, rng)
+            .unwrap();
+        let child_visits = tree.get_root_child_visits();
+        let total_visits: f64 = child_visits.iter().map(|&(_, visits)| visits).sum();
+
+        let mut visit_shares = [0.0; CELL_COUNT];
+        for &((row_index, col_index), visits) in child_visits.iter() {
+            visit_shares[row_index * 3 + col_index] = visits / total_visits.max(1.0);
+        }
+
+        samples.push(TrainingSample {
+            cells: policy_value_net::get_cells(&game),
+            turn: game.get_turn(),
+            visit_shares,
+            outcome: 0.0,
+        });
+
+        let game_move = if move_count < config.sampling_moves {
+            sample_by_visits(&child_visits, rng)
+        } else {
+            best_move
+        };
+
+        game.play_move(game_move);
+        tree.advance_root(&[game_move]).unwrap();
+        move_count += 1;
+    }
+
+    let game_result = game.get_outcome().unwrap();
+    for sample in samples.iter_mut() {
+        sample.outcome = match game_result {
+            GameOutcome::Won(winner) if winner == sample.turn => 1.0,
+            GameOutcome::Won(_) => 0.0,
+            GameOutcome::Tie => search_config.draw_value,
+        };
+    }
+
+    samples
+}
+
+/// Writes `samples` to `writer`, one per line: the cells row by row (`X`, `O` or `.`), the player to move, the
+/// visit shares of the cells row by row, and the outcome, separated by spaces
+pub fn write_samples<W: Write>(writer: &mut W, samples: &[TrainingSample]) -> io::Result<()> {
+    for sample in samples {
+        let cells: String = sample
+            .cells
+            .iter()
+            .map(|cell| match cell {
+                board::Cell::X => 'X',
+                board::Cell::O => 'O',
+                board::Cell::Empty => '.',
+            })
+            .collect();
+        let turn = match sample.turn {
+            game::GameTurn::TurnX => "X",
+            game::GameTurn::TurnO => "O",
+        };
+        let visit_shares: Vec<String> = sample
+            .visit_shares
+            .iter()
+            .map(|share| share.to_string())
+            .collect();
+
+        writeln!(
+            writer,
+            "{} {} {} {}",
+            cells,
+            turn,
+            visit_shares.join(" "),
+            sample.outcome
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Reads samples written by `write_samples` from `reader`. Returns an `Err` if a line is not a valid sample
+pub fn read_samples<R: BufRead>(reader: R) -> io::Result<Vec<TrainingSample>> {
+    let invalid_data = || io::Error::new(io::ErrorKind::InvalidData, "Invalid training sample.");
+    let mut samples = Vec::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 3 + CELL_COUNT || fields[0].chars().count() != CELL_COUNT {
+            return Err(invalid_data());
+        }
+
+        let mut cells = [board::Cell::Empty; CELL_COUNT];
+        for (cell, symbol) in cells.iter_mut().zip(fields[0].chars()) {
+            *cell = match symbol {
+                'X' => board::Cell::X,
+                'O' => board::Cell::O,
+                '.' => board::Cell::Empty,
+                _ => return Err(invalid_data()),
+            };
+        }
+
+        let turn = match fields[1] {
+            "X" => game::GameTurn::TurnX,
+            "O" => game::GameTurn::TurnO,
+            _ => return Err(invalid_data()),
+        };
+
+        let mut visit_shares = [0.0; CELL_COUNT];
+        for (share, field) in visit_shares.iter_mut().zip(&fields[2..2 + CELL_COUNT]) {
+            *share = field.parse().map_err(|_| invalid_data())?;
+        }
+
+        let outcome = fields[2 + CELL_COUNT].parse().map_err(|_| invalid_data())?;
+
+        samples.push(TrainingSample {
+            cells,
+            turn,
+            visit_shares,
+            outcome,
+        });
+    }
+
+    Ok(samples)
+}
+
+/// Trains `net` by self-play for the generations in `config`, calling `on_generation` after each of them.
+/// Training is skipped while the replay buffer is empty, as when no self-play game is played. Returns an `Err`
+/// if the samples cannot be written to `config.samples_path`
+pub fn train<R: Rng + ?Sized, F: FnMut(&GenerationReport)>(
+    net: &mut PolicyValueNet,
+    config: &TrainingConfig,
+    rng: &mut R,
+    mut on_generation: F,
+) -> io::Result<()> {
+    let mut samples_file = match &config.samples_path {
+        Some(samples_path) => Some(io::BufWriter::new(
+            fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(samples_path)?,
+        )),
+        None => None,
+    };
+    let mut replay_buffer: collections::VecDeque<TrainingSample> = collections::VecDeque::new();
+
+    for generation in 0..config.generations {
+        let search_net = sync::Arc::new(net.clone());
+
+        for _ in 0..config.games_per_generation {
+            let samples = play_self_play_game(&search_net, config, rng);
+
+            if let Some(samples_file) = &mut samples_file {
+                write_samples(samples_file, &samples)?;
+            }
+
+            replay_buffer.extend(samples);
+            while replay_buffer.len() > config.replay_capacity {
+                replay_buffer.pop_front();
+            }
+        }
+
+        let training_steps = if replay_buffer.is_empty() {
+            0
+        } else {
+            config.training_steps
+        };
+
+        let mut total_loss = 0.0;
+        for _ in 0..training_steps {
+            let batch: Vec<TrainingSample> = (0..config.batch_size)
+                .map(|_| replay_buffer[rng.gen_range(0..replay_buffer.len())].clone())
+                .collect();
+            total_loss += net.train_batch(&batch, config.learning_rate, config.weight_decay);
+        }
+
+        on_generation(&GenerationReport {
+            generation,
+            sample_count: replay_buffer.len(),
+            loss: total_loss / training_steps.max(1) as f64,
+        });
+    }
+
+    if let Some(samples_file) = &mut samples_file {
+        samples_file.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs, SeedableRng};
+
+    fn small_config() -> TrainingConfig {
+        TrainingConfig {
+            generations: 2,
+            games_per_generation: 3,
+            search_iterations: 20,
+            training_steps: 5,
+            batch_size: 8,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_play_self_play_game() {
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let net = sync::Arc::new(PolicyValueNet::new(&[16], &mut rng));
+        let samples = play_self_play_game(&net, &small_config(), &mut rng);
+
+        // A game lasts from 5 to 9 moves, with one sample per move
+        assert!(samples.len() >= 5 && samples.len() <= 9);
+        assert_eq!(samples[0].cells, [board::Cell::Empty; CELL_COUNT]);
+
+        for sample in samples.iter() {
+            assert!((sample.visit_shares.iter().sum::<f64>() - 1.0).abs() < 1e-7);
+            assert!([0.0, 0.5, 1.0].contains(&sample.outcome));
+        }
+
+        // Consecutive positions are seen by opposite players, who get opposite outcomes
+        assert!((samples[0].outcome + samples[1].outcome - 1.0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_write_and_read_samples() {
+        let mut rng = rngs::StdRng::seed_from_u64(1);
+        let net = sync::Arc::new(PolicyValueNet::new(&[16], &mut rng));
+        let samples = play_self_play_game(&net, &small_config(), &mut rng);
+
+        let mut buffer = Vec::new();
+        write_samples(&mut buffer, &samples).unwrap();
+
+        assert_eq!(read_samples(buffer.as_slice()).unwrap(), samples);
+        assert!(read_samples("XO. X 1".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_train_reports_generations() {
+        let mut rng = rngs::StdRng::seed_from_u64(2);
+        let mut net = PolicyValueNet::new(&[16], &mut rng);
+        let initial_net = net.clone();
+        let mut reports = Vec::new();
+
+        train(&mut net, &small_config(), &mut rng, |report| {
+            reports.push(report.clone())
+        })
+        .unwrap();
+
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[1].generation, 1);
+        assert!(reports[1].sample_count >= 2 * 3 * 5);
+        assert!(reports.iter().all(|report| report.loss.is_finite()));
+        assert_ne!(net, initial_net);
+    }
+
+    #[test]
+    fn test_train_without_games() {
+        let mut rng = rngs::StdRng::seed_from_u64(3);
+        let mut net = PolicyValueNet::new(&[16], &mut rng);
+        let initial_net = net.clone();
+        let config = TrainingConfig {
+            games_per_generation: 0,
+            ..small_config()
+        };
+        let mut reports = Vec::new();
+
+        train(&mut net, &config, &mut rng, |report| {
+            reports.push(report.clone())
+        })
+        .unwrap();
+
+        // Without samples, generations are reported but the network is left untouched
+        assert_eq!(reports.len(), 2);
+        assert!(reports.iter().all(|report| report.sample_count == 0));
+        assert_eq!(net, initial_net);
+    }
+}
@@ -0,0 +1,615 @@
+//! Contains a tree-parallel MCTS (Monte Carlo Tree Search) driver, where several threads cooperate on one
+//! shared search tree
+//!
+//! Node statistics are atomics, so threads update them concurrently while holding a shared read lock on the
+//! node arena. Only expansion takes the exclusive write lock. While a thread walks down the tree, it adds a
+//! virtual loss to every node on its path, which counts as a visit without a win when the tree policy scores
+//! the node, until the playout result is backpropagated, so other threads spread over different branches.
+//!
+//! Expansion adds all children of a leaf at once and plays out each of them. Single-child expansion, root
+//! parallelism, RAVE, evaluators and the solver are not supported, and `check_config` rejects them.
+//!
+//! ## Examples
+//!
+//! ```
+//! use mcts::{search_config, shared_tree};
+//! use tic_tac_toe::game;
+//!
+//! let config = search_config::SearchConfig {
+//!     budget: search_config::SearchBudget::Iterations(200),
+//!     parallelism: search_config::Parallelism::TreeParallel { threads: 4 },
+//!     ..Default::default()
+//! };
+//!
+//! let mut tree = shared_tree::SharedTree::new(&game::Game::new());
+//! let best_move = tree.think_about_best_move(&config);
+//! assert!(best_move.is_some());
+//! ```
+
+use crate::mcts_core::{MCTree, NodeIndex, ROOT};
+use crate::mcts_game::{GameOutcome, MCTSGame};
+use crate::search_config::{ExpansionMode, Parallelism, SearchConfig, SearchConfigError};
+use crate::tree_policy::ChildStats;
+use rand::{rngs, Rng, SeedableRng};
+use std::sync::{self, atomic};
+use std::thread;
+use std::time;
+
+/// Represents a node in the shared Monte Carlo tree. Includes
+/// - game state
+/// - parent which is the game state we reached current game state from
+/// - children which are the possible game states reachable from current state
+/// - game move played in parent state to reach current node (None if parent is None)
+/// - number of wins, stored as the bits of an `f64`
+/// - sum of squared rewards, stored as the bits of an `f64`
+/// - number of visits
+/// - number of threads currently walking through the node (virtual losses)
+/// - flag claimed by the single thread allowed to expand the node
+struct SharedNode<G: MCTSGame> {
+    game: G,
+    parent: Option<NodeIndex>,
+    children: Vec<NodeIndex>,
+    move_from_parent: Option<G::Move>,
+    wins: atomic::AtomicU64,
+    squared_rewards: atomic::AtomicU64,
+    visits: atomic::AtomicU64,
+    virtual_losses: atomic::AtomicU64,
+    expansion_claimed: atomic::AtomicBool,
+}
+
+impl<G: MCTSGame> SharedNode<G> {
+    fn new(game: G, parent: Option<NodeIndex>, move_from_parent: Option<G::Move>) -> Self {
+        SharedNode {
+            game,
+            parent,
+            children: Vec::new(),
+            move_from_parent,
+            wins: atomic::AtomicU64::new(0.0_f64.to_bits()),
+            squared_rewards: atomic::AtomicU64::new(0.0_f64.to_bits()),
+            visits: atomic::AtomicU64::new(0),
+            virtual_losses: atomic::AtomicU64::new(0),
+            expansion_claimed: atomic::AtomicBool::new(false),
+        }
+    }
+
+    fn get_wins(&self) -> f64 {
+        f64::from_bits(self.wins.load(atomic::Ordering::Relaxed))
+    }
+
+    fn get_squared_rewards(&self) -> f64 {
+        f64::from_bits(self.squared_rewards.load(atomic::Ordering::Relaxed))
+    }
+
+    fn get_visits(&self) -> f64 {
+        self.visits.load(atomic::Ordering::Relaxed) as f64
+    }
+
+    /// Atomically adds `reward` to the wins of the node, and its square to the squared rewards
+    fn add_reward(&self, reward: f64) {
+        Self::add_to(&self.wins, reward);
+        Self::add_to(&self.squared_rewards, reward * reward);
+    }
+
+    /// Atomically adds `value` to the `f64` stored as bits in `total`
+    fn add_to(total: &atomic::AtomicU64, value: f64) {
+        let mut current = total.load(atomic::Ordering::Relaxed);
+
+        loop {
+            let updated = (f64::from_bits(current) + value).to_bits();
+            match total.compare_exchange_weak(
+                current,
+                updated,
+                atomic::Ordering::Relaxed,
+                atomic::Ordering::Relaxed,
+            ) {
+                Ok(_) => return,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
+
+/// Represents a Monte Carlo search tree that several threads can search at once. All nodes are owned by a
+/// single arena behind a read-write lock, with the root node stored first
+pub struct SharedTree<G: MCTSGame> {
+    nodes: sync::RwLock<Vec<SharedNode<G>>>,
+}
+
+impl<G: MCTSGame> SharedTree<G> {
+    /// Returns a newly created shared search tree whose root node holds `game_state`
+    pub fn new(game_state: &G) -> Self {
+        SharedTree {
+            nodes: sync::RwLock::new(vec![SharedNode::new(game_state.clone(), None, None)]),
+        }
+    }
+
+    /// Gets the number of nodes in the tree
+    pub fn get_node_count(&self) -> usize {
+        self.nodes.read().unwrap().len()
+    }
+
+    /// Gets the number of visits (i.e. backpropagated playouts) of the root node
+    pub fn get_root_visits(&self) -> f64 {
+        self.nodes.read().unwrap()[ROOT].get_visits()
+    }
+
+    /// Checks that this driver supports every option in `config`. Returns an `Err` for the first option it does not
+    /// support, among single-child expansion, root parallelism, RAVE, an evaluator and the solver
+    pub fn check_config(config: &SearchConfig<G>) -> Result<(), SearchConfigError> {
+        if config.expansion != ExpansionMode::All {
+            return Err(SearchConfigError::UnsupportedExpansion);
+        }
+
+        if let Parallelism::RootParallel { .. } = config.parallelism {
+            return Err(SearchConfigError::UnsupportedParallelism);
+        }
+
+        if config.rave_equivalence.is_some() {
+            return Err(SearchConfigError::UnsupportedRave);
+        }
+
+        if config.evaluator.is_some() {
+            return Err(SearchConfigError::UnsupportedEvaluator);
+        }
+
+        if config.solver {
+            return Err(SearchConfigError::UnsupportedSolver);
+        }
+
+        Ok(())
+    }
+
+    /// Gets the effective visits of `node`, counting every virtual loss as `config.virtual_loss` visits
+    fn get_effective_visits(node: &SharedNode<G>, config: &SearchConfig<G>) -> f64 {
+        node.get_visits()
+            + config.virtual_loss * node.virtual_losses.load(atomic::Ordering::Relaxed) as f64
+    }
+
+    /// Gets the statistics the tree policy scores `child` with, where virtual losses count as visits without a
+    /// win. Children with neither visits nor virtual losses start from `config.draw_value`
+    fn get_child_stats(child: &SharedNode<G>, config: &SearchConfig<G>) -> ChildStats {
+        let effective_visits = Self::get_effective_visits(child, config);
+        let wins = child.get_wins();
+
+        ChildStats {
+            value: if effective_visits > 0.0 {
+                wins / effective_visits
+            } else {
+                config.draw_value
+            },
+            wins,
+            squared_rewards: child.get_squared_rewards(),
+            visits: effective_visits,
+            prior: 0.0,
+        }
+    }
+
+    /// Navigate from the root until a leaf node is reached based on the tree policy in `config`, adding a virtual
+    /// loss to every node selected on the way. Returns the path from the root to the leaf
+    fn select_path<R: Rng + ?Sized>(
+        &self,
+        nodes: &[SharedNode<G>],
+        config: &SearchConfig<G>,
+        rng: &mut R,
+    ) -> Vec<NodeIndex> {
+        let tree_policy = MCTree::<G>::get_tree_policy(config);
+        let mut rng = rng;
+        let mut path = vec![ROOT];
+        let mut node = ROOT;
+
+        loop {
+            // A parent with less than one visit, which only counts virtual losses left by other threads, gives no
+            // exploration bonus
+            let parent_visits = Self::get_effective_visits(&nodes[node], config).max(1.0);
+            let mut max_score_child: Option<(NodeIndex, f64)> = None;
+
+            for &child in nodes[node].children.iter() {
+                let score = tree_policy.score(
+                    parent_visits,
+                    &Self::get_child_stats(&nodes[child], config),
+                    config.exploration_constant,
+                    &mut rng,
+                );
+
+                if max_score_child.is_none_or(|(_, max_score)| score > max_score) {
+                    max_score_child = Some((child, score));
+                }
+            }
+
+            match max_score_child {
+                Some((child, _)) => {
+                    nodes[child]
+                        .virtual_losses
+                        .fetch_add(1, atomic::Ordering::Relaxed);
+                    path.push(child);
+                    node = child;
+                }
+                None => return path,
+            }
+        }
+    }
+
+    /// Adds children corresponding to all possible next moves of `node`, which must have been claimed for
+    /// expansion by the calling thread. With symmetries merged, only one move is kept for every group of moves
+    /// leading to symmetric positions. Returns the indices of the new children
+    fn expand_node(&self, node: NodeIndex, game: &G, config: &SearchConfig<G>) -> Vec<NodeIndex> {
+        let possible_plays = if config.merge_symmetries {
+            game.get_distinct_plays()
+        } else {
+            game.get_possible_plays()
+        };
+        let child_nodes: Vec<SharedNode<G>> = possible_plays
+            .into_iter()
+            .map(|game_move| {
+                SharedNode::new(game.get_played_move(game_move), Some(node), Some(game_move))
+            })
+            .collect();
+
+        let mut nodes = self.nodes.write().unwrap();
+        let first_child = nodes.len();
+        nodes.extend(child_nodes);
+
+        let children: Vec<NodeIndex> = (first_child..nodes.len()).collect();
+        nodes[node].children = children.clone();
+        children
+    }
+
+    /// Refresh the state of wins/visits from `node` up the tree until root node is reached. Wins are counted from
+    /// the perspective of the player who made the move leading to each node, who may be the player to move in it
+    fn backpropagate(
+        nodes: &[SharedNode<G>],
+        node: NodeIndex,
+        game_result: GameOutcome<G::Player>,
+        config: &SearchConfig<G>,
+    ) {
+        let mut current = Some(node);
+
+        while let Some(node) = current {
+            let node_player = nodes[node].game.get_turn();
+            let repeats_turn = nodes[node]
+                .parent
+                .is_some_and(|parent| nodes[parent].game.get_turn() == node_player);
+            let reward = MCTree::<G>::reward(game_result, node_player, repeats_turn, config);
+
+            nodes[node].visits.fetch_add(1, atomic::Ordering::Relaxed);
+            nodes[node].add_reward(reward);
+
+            current = nodes[node].parent;
+        }
+    }
+
+    /// Perform one round of an MCTS update from the calling thread. This includes:
+    /// 1- selecting a leaf node starting from root according to the tree policy with virtual losses
+    /// 2- expanding leaf node to include its children of possible new moves, unless another thread is already
+    ///    expanding it or the game is over at the leaf
+    /// 3- simulating a random playout starting from each of the new children, or from the leaf itself
+    /// 4- backpropagating game results of random playouts up to the root node, then removing the virtual losses
+    fn mcts_update<R: Rng + ?Sized>(&self, config: &SearchConfig<G>, rng: &mut R) {
+        let (path, leaf_game, claimed) = {
+            let nodes = self.nodes.read().unwrap();
+            let path = self.select_path(&nodes, config, rng);
+            let leaf = &nodes[*path.last().unwrap()];
+            let claimed = !leaf.game.is_over()
+                && !leaf.expansion_claimed.swap(true, atomic::Ordering::Relaxed);
+
+            (path, leaf.game.clone(), claimed)
+        };
+
+        let leaf = *path.last().unwrap();
+        let playout_starts = if claimed {
+            self.expand_node(leaf, &leaf_game, config)
+        } else {
+            vec![leaf]
+        };
+
+        // Playouts run without holding the lock, so that expansions by other threads are not blocked
+        let playout_games: Vec<G> = {
+            let nodes = self.nodes.read().unwrap();
+            playout_starts
+                .iter()
+                .map(|&node| nodes[node].game.clone())
+                .collect()
+        };
+
+        let game_results: Vec<(NodeIndex, GameOutcome<G::Player>)> = playout_starts
+            .iter()
+            .zip(playout_games.iter())
+            .map(|(&node, game)| (node, MCTree::<G>::simulate_playout(game, config, rng).0))
+            .collect();
+
+        let nodes = self.nodes.read().unwrap();
+        for (node, game_result) in game_results {
+            Self::backpropagate(&nodes, node, game_result, config);
+        }
+
+        for &node in path.iter().skip(1) {
+            nodes[node]
+                .virtual_losses
+                .fetch_sub(1, atomic::Ordering::Relaxed);
+        }
+    }
+
+    /// Runs MCTS iterations from the calling thread until the shared budget is used up or a limit is hit. Every
+    /// thread claims its iterations from the shared `iterations` counter
+    fn run_worker<R: Rng + ?Sized>(
+        &self,
+        config: &SearchConfig<G>,
+        start: time::Instant,
+        iterations: &atomic::AtomicU32,
+        rng: &mut R,
+    ) {
+        while !MCTree::<G>::is_budget_used(
+            config,
+            start,
+            iterations.fetch_add(1, atomic::Ordering::Relaxed),
+            self.get_node_count(),
+        ) {
+            self.mcts_update(config, rng);
+        }
+    }
+
+    /// Performes iterations of MCTS algorithm on the threads given by `config.parallelism`, all sharing this tree,
+    /// until the budget in `config` is used up or one of its limits is hit, and responds with best move found so
+    /// far. Sequential search runs a single thread. The iteration budget is shared by all threads. A move is always
+    /// returned unless the game is already over. Runs on several threads are not reproducible since threads
+    /// interleave freely. Panics in debug builds if `check_config` rejects `config`, and ignores the unsupported
+    /// options in release builds
+    pub fn think_about_best_move(&mut self, config: &SearchConfig<G>) -> Option<G::Move> {
+        let supported = Self::check_config(config);
+        debug_assert!(
+            supported.is_ok(),
+            "Unsupported search config: {:?}",
+            supported
+        );
+
+        let threads = match config.parallelism {
+            Parallelism::TreeParallel { threads } => threads,
+            _ => 1,
+        };
+        let base_seed = config.seed.unwrap_or_else(rand::random);
+        let start = time::Instant::now();
+        let iterations = atomic::AtomicU32::new(0);
+
+        thread::scope(|scope| {
+            for thread_index in 0..threads.max(1) {
+                let seed = base_seed.wrapping_add(thread_index as u64);
+                let tree = &*self;
+                let iterations = &iterations;
+                scope.spawn(move || {
+                    let mut rng = rngs::StdRng::seed_from_u64(seed);
+                    tree.run_worker(config, start, iterations, &mut rng);
+                });
+            }
+        });
+
+        let nodes = self.nodes.get_mut().unwrap();
+
+        // Make sure a move can be returned even if the limits stopped the search before any expansion
+        if nodes[ROOT].children.is_empty() && !nodes[ROOT].game.is_over() {
+            let root_game = nodes[ROOT].game.clone();
+            self.expand_node(ROOT, &root_game, config);
+        }
+
+        let nodes = self.nodes.get_mut().unwrap();
+        let children: Vec<(G::Move, f64, f64)> = nodes[ROOT]
+            .children
+            .iter()
+            .map(|&child| {
+                let node = &nodes[child];
+                (
+                    node.move_from_parent.unwrap(),
+                    node.get_wins(),
+                    node.get_visits(),
+                )
+            })
+            .collect();
+
+        MCTree::<G>::choose_final_move(&children, nodes[ROOT].get_visits(), config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_config::SearchBudget;
+    use tic_tac_toe::game;
+
+    fn iterations_config(iterations: u32) -> SearchConfig<game::Game> {
+        SearchConfig {
+            budget: SearchBudget::Iterations(iterations),
+            ..Default::default()
+        }
+    }
+
+    fn threads_config(iterations: u32, threads: usize) -> SearchConfig<game::Game> {
+        SearchConfig {
+            parallelism: Parallelism::TreeParallel { threads },
+            ..iterations_config(iterations)
+        }
+    }
+
+    #[test]
+    fn test_select_path_applies_virtual_loss() {
+        let tree = SharedTree::new(&game::Game::new());
+        let root_game = game::Game::new();
+        let config = SearchConfig::default();
+        tree.expand_node(ROOT, &root_game, &config);
+
+        let mut rng = rngs::StdRng::seed_from_u64(5);
+        let nodes = tree.nodes.read().unwrap();
+
+        // Give every child one visit without a win, so selection is decided by virtual losses alone
+        for &child in nodes[ROOT].children.iter() {
+            nodes[child].visits.fetch_add(1, atomic::Ordering::Relaxed);
+            nodes[ROOT].visits.fetch_add(1, atomic::Ordering::Relaxed);
+        }
+
+        let first_path = tree.select_path(&nodes, &config, &mut rng);
+        let second_path = tree.select_path(&nodes, &config, &mut rng);
+
+        // The virtual loss left by the first walk steers the second walk to a different child
+        assert_eq!(first_path.len(), 2);
+        assert_eq!(second_path.len(), 2);
+        assert_ne!(first_path[1], second_path[1]);
+        assert_eq!(
+            nodes[first_path[1]]
+                .virtual_losses
+                .load(atomic::Ordering::Relaxed),
+            1
+        );
+    }
+
+    #[test]
+    fn test_select_path_unvisited_children() {
+        let tree = SharedTree::new(&game::Game::new());
+        let root_game = game::Game::new();
+        let config = SearchConfig::default();
+        tree.expand_node(ROOT, &root_game, &config);
+
+        let mut rng = rngs::StdRng::seed_from_u64(5);
+        let nodes = tree.nodes.read().unwrap();
+
+        // Neither the root nor its children have visits yet, and every walk takes a child nobody else is in
+        let mut selected_children: Vec<NodeIndex> = (0..9)
+            .map(|_| tree.select_path(&nodes, &config, &mut rng)[1])
+            .collect();
+        selected_children.sort();
+        assert_eq!(selected_children, nodes[ROOT].children);
+    }
+
+    #[test]
+    fn test_mcts_update_visits_every_root_child() {
+        // Root children are added without playouts, as when another thread is still running them
+        let tree = SharedTree::new(&game::Game::new());
+        let root_game = game::Game::new();
+        let config = SearchConfig::default();
+        tree.expand_node(ROOT, &root_game, &config);
+        tree.nodes.read().unwrap()[ROOT]
+            .expansion_claimed
+            .store(true, atomic::Ordering::Relaxed);
+
+        let mut rng = rngs::StdRng::seed_from_u64(5);
+        for _ in 0..9 {
+            tree.mcts_update(&config, &mut rng);
+        }
+
+        // Every update expands one child and runs playouts below it
+        let nodes = tree.nodes.read().unwrap();
+        assert_eq!(nodes[ROOT].children.len(), 9);
+        for &child in nodes[ROOT].children.iter() {
+            assert!(nodes[child].get_visits() > 0.0);
+            assert!(!nodes[child].children.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_mcts_update_clears_virtual_losses() {
+        let tree = SharedTree::new(&game::Game::new());
+        let config = SearchConfig::default();
+        let mut rng = rngs::StdRng::seed_from_u64(5);
+
+        for _ in 0..50 {
+            tree.mcts_update(&config, &mut rng);
+        }
+
+        let nodes = tree.nodes.read().unwrap();
+        for node in nodes.iter() {
+            assert_eq!(node.virtual_losses.load(atomic::Ordering::Relaxed), 0);
+        }
+    }
+
+    #[test]
+    fn test_think_about_best_move_threads() {
+        let mut tree = SharedTree::new(&game::Game::new());
+        let best_move = tree.think_about_best_move(&threads_config(300, 4));
+
+        assert!(game::Game::new()
+            .get_possible_plays()
+            .contains(&best_move.unwrap()));
+
+        // Every non-root node received a playout when it was added
+        assert!(tree.get_root_visits() >= (tree.get_node_count() - 1) as f64);
+
+        // Root visits cover its children, plus any playouts run from the root while it was being expanded
+        let nodes = tree.nodes.read().unwrap();
+        let child_visits: f64 = nodes[ROOT]
+            .children
+            .iter()
+            .map(|&child| nodes[child].get_visits())
+            .sum();
+        assert!(child_visits <= nodes[ROOT].get_visits());
+    }
+
+    #[test]
+    fn test_think_about_best_move_without_iterations() {
+        let mut tree = SharedTree::new(&game::Game::new());
+        assert!(tree.think_about_best_move(&threads_config(0, 2)).is_some());
+    }
+
+    #[test]
+    fn test_check_config() {
+        assert_eq!(SharedTree::check_config(&threads_config(10, 4)), Ok(()));
+        assert_eq!(SharedTree::check_config(&iterations_config(10)), Ok(()));
+
+        let config = SearchConfig {
+            parallelism: Parallelism::RootParallel { threads: 4 },
+            ..iterations_config(10)
+        };
+        assert_eq!(
+            SharedTree::check_config(&config),
+            Err(SearchConfigError::UnsupportedParallelism)
+        );
+
+        let config = SearchConfig {
+            solver: true,
+            ..threads_config(10, 4)
+        };
+        assert_eq!(
+            SharedTree::check_config(&config),
+            Err(SearchConfigError::UnsupportedSolver)
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Unsupported search config")]
+    fn test_think_about_best_move_rejects_evaluator() {
+        let config = SearchConfig {
+            evaluator: Some(sync::Arc::new(crate::evaluator::RolloutEvaluator)),
+            ..threads_config(10, 2)
+        };
+
+        SharedTree::new(&game::Game::new()).think_about_best_move(&config);
+    }
+
+    #[test]
+    fn test_think_about_best_move_merge_symmetries() {
+        let config = SearchConfig {
+            merge_symmetries: true,
+            ..threads_config(100, 2)
+        };
+
+        // Corner, edge and centre openings are the only distinct moves of the empty board
+        let mut tree = SharedTree::new(&game::Game::new());
+        let best_move = tree.think_about_best_move(&config);
+
+        assert_eq!(tree.nodes.read().unwrap()[ROOT].children.len(), 3);
+        assert!([(0, 0), (0, 1), (1, 1)].contains(&best_move.unwrap()));
+    }
+
+    #[test]
+    fn test_think_about_best_move_tree_policy() {
+        // X to move wins at (0, 2), which UCB1-Tuned finds
+        let mut game = game::Game::new();
+        for (row_index, col_index) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            game.play(row_index, col_index).unwrap();
+        }
+        let config = SearchConfig {
+            tree_policy: Some(sync::Arc::new(crate::tree_policy::Ucb1Tuned)),
+            ..threads_config(300, 4)
+        };
+
+        let mut tree = SharedTree::new(&game);
+        assert_eq!(tree.think_about_best_move(&config), Some((0, 2)));
+    }
+}
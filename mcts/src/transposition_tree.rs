@@ -0,0 +1,541 @@
+//! Contains a transposition-aware MCTS (Monte Carlo Tree Search) driver, which searches a DAG (Directed Acyclic
+//! Graph) instead of a tree
+//!
+//! A plain `MCTree` builds a separate node for every move order leading to a position. Here, a transposition
+//! table maps every game state to a single node, so positions reached through different move orders share
+//! their statistics. Statistics follow UCT3: a node holds the wins and visits of its position, whichever path
+//! the playouts came through, while every edge counts how often its move was tried from its parent. Selection
+//! scores a move with the win rate of the node it leads to, and explores according to the visits of the edge.
+//!
+//! Expansion adds all children of a leaf at once and plays out each of them. Single-child expansion, root
+//! parallelism, RAVE, evaluators and the solver are not supported, and `check_config` rejects them.
+//!
+//! ## Examples
+//!
+//! ```
+//! use mcts::{search_config, transposition_tree};
+//! use tic_tac_toe::game;
+//!
+//! let config = search_config::SearchConfig {
+//!     budget: search_config::SearchBudget::Iterations(200),
+//!     ..Default::default()
+//! };
+//!
+//! let mut tree = transposition_tree::TranspositionTree::new(&game::Game::new());
+//! let best_move = tree.think_about_best_move(&config);
+//! assert!(best_move.is_some());
+//! ```
+
+use crate::mcts_core::{MCTree, NodeIndex, ROOT};
+use crate::mcts_game::{GameOutcome, MCTSGame};
+use crate::search_config::{ExpansionMode, Parallelism, SearchConfig, SearchConfigError};
+use crate::tree_policy::ChildStats;
+use rand::Rng;
+use std::collections;
+use std::hash;
+use std::time;
+
+/// Represents a move from a node to one of its children. Includes
+/// - game move played in the parent state
+/// - child node reached by the move
+/// - number of visits of the parent that went through the move
+struct Edge<G: MCTSGame> {
+    game_move: G::Move,
+    child: NodeIndex,
+    visits: f64,
+}
+
+/// Represents a position in the search DAG. Includes
+/// - game state
+/// - edges to the game states reachable from current state
+/// - number of wins, from the perspective of the player who moved into the position
+/// - sum of squared rewards, giving the variance of rewards together with the number of wins
+/// - number of visits, summed over all the paths leading to the position
+struct DagNode<G: MCTSGame> {
+    game: G,
+    edges: Vec<Edge<G>>,
+    wins: f64,
+    squared_rewards: f64,
+    visits: f64,
+}
+
+impl<G: MCTSGame> DagNode<G> {
+    fn new(game: G) -> Self {
+        DagNode {
+            game,
+            edges: Vec::new(),
+            wins: 0.0,
+            squared_rewards: 0.0,
+            visits: 0.0,
+        }
+    }
+
+    /// Gets the win rate of the position, or `draw_value` if it was never visited
+    fn get_win_rate(&self, draw_value: f64) -> f64 {
+        if self.visits > 0.0 {
+            self.wins / self.visits
+        } else {
+            draw_value
+        }
+    }
+}
+
+/// Represents a Monte Carlo search DAG. All nodes are owned by a single arena, with the root node stored first,
+/// and the transposition table maps every game state in the arena to its node
+pub struct TranspositionTree<G: MCTSGame + Eq + hash::Hash> {
+    nodes: Vec<DagNode<G>>,
+    table: collections::HashMap<G, NodeIndex>,
+}
+
+impl<G: MCTSGame + Eq + hash::Hash> TranspositionTree<G> {
+    /// Returns a newly created search DAG whose root node holds `game_state`
+    pub fn new(game_state: &G) -> Self {
+        TranspositionTree {
+            nodes: vec![DagNode::new(game_state.clone())],
+            table: collections::HashMap::from([(game_state.clone(), ROOT)]),
+        }
+    }
+
+    /// Gets the number of nodes, i.e. distinct positions, in the DAG
+    pub fn get_node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Gets the number of edges in the DAG. A plain tree would need one node per edge, besides the root
+    pub fn get_edge_count(&self) -> usize {
+        self.nodes.iter().map(|node| node.edges.len()).sum()
+    }
+
+    /// Gets the number of visits (i.e. backpropagated playouts) of the root node
+    pub fn get_root_visits(&self) -> f64 {
+        self.nodes[ROOT].visits
+    }
+
+    /// Checks that this driver supports every option in `config`. Returns an `Err` for the first option it does not
+    /// support, among single-child expansion, root parallelism, RAVE, an evaluator and the solver
+    pub fn check_config(config: &SearchConfig<G>) -> Result<(), SearchConfigError> {
+        if config.expansion != ExpansionMode::All {
+            return Err(SearchConfigError::UnsupportedExpansion);
+        }
+
+        if config.parallelism != Parallelism::Sequential {
+            return Err(SearchConfigError::UnsupportedParallelism);
+        }
+
+        if config.rave_equivalence.is_some() {
+            return Err(SearchConfigError::UnsupportedRave);
+        }
+
+        if config.evaluator.is_some() {
+            return Err(SearchConfigError::UnsupportedEvaluator);
+        }
+
+        if config.solver {
+            return Err(SearchConfigError::UnsupportedSolver);
+        }
+
+        Ok(())
+    }
+
+    /// Gets the statistics the tree policy scores `edge` with, following UCT3: the win rate of the child node it
+    /// leads to, and the visits of the edge. Wins and squared rewards are scaled down to the visits of the edge
+    fn get_edge_stats(&self, edge: &Edge<G>, config: &SearchConfig<G>) -> ChildStats {
+        let child = &self.nodes[edge.child];
+        let win_rate = child.get_win_rate(config.draw_value);
+        let squared_reward_rate = if child.visits > 0.0 {
+            child.squared_rewards / child.visits
+        } else {
+            0.0
+        };
+
+        ChildStats {
+            value: win_rate,
+            wins: win_rate * edge.visits,
+            squared_rewards: squared_reward_rate * edge.visits,
+            visits: edge.visits,
+            prior: 0.0,
+        }
+    }
+
+    /// Navigate from the root until a leaf node is reached based on the tree policy in `config`, which scores
+    /// every edge with `get_edge_stats`. Returns the nodes on the path from the root to the leaf, together with
+    /// the index of the edge taken out of every node but the leaf
+    fn select_path<R: Rng + ?Sized>(
+        &self,
+        config: &SearchConfig<G>,
+        rng: &mut R,
+    ) -> (Vec<NodeIndex>, Vec<usize>) {
+        let tree_policy = MCTree::<G>::get_tree_policy(config);
+        let mut rng = rng;
+        let mut path = vec![ROOT];
+        let mut edge_indices = Vec::new();
+        let mut node = ROOT;
+
+        loop {
+            let parent_visits = self.nodes[node].visits;
+            let mut max_score_edge: Option<(usize, f64)> = None;
+
+            for (edge_index, edge) in self.nodes[node].edges.iter().enumerate() {
+                let score = tree_policy.score(
+                    parent_visits,
+                    &self.get_edge_stats(edge, config),
+                    config.exploration_constant,
+                    &mut rng,
+                );
+
+                if max_score_edge.is_none_or(|(_, max_score)| score > max_score) {
+                    max_score_edge = Some((edge_index, score));
+                }
+            }
+
+            match max_score_edge {
+                Some((edge_index, _)) => {
+                    node = self.nodes[node].edges[edge_index].child;
+                    edge_indices.push(edge_index);
+                    path.push(node);
+                }
+                None => return (path, edge_indices),
+            }
+        }
+    }
+
+    /// Starting from `node`, adds edges corresponding to all possible next moves. A child position already in the
+    /// transposition table is shared instead of being added again. With symmetries merged, only one move is kept
+    /// for every group of moves leading to symmetric positions
+    fn expand_node(&mut self, node: NodeIndex, config: &SearchConfig<G>) {
+        if !self.nodes[node].edges.is_empty() {
+            panic!("Cannot expand a non-leaf node!");
+        }
+
+        let possible_plays = if config.merge_symmetries {
+            self.nodes[node].game.get_distinct_plays()
+        } else {
+            self.nodes[node].game.get_possible_plays()
+        };
+
+        for game_move in possible_plays {
+            let game = self.nodes[node].game.get_played_move(game_move);
+
+            let child = match self.table.get(&game) {
+                Some(&child) => child,
+                None => {
+                    let child = self.nodes.len();
+                    self.table.insert(game.clone(), child);
+                    self.nodes.push(DagNode::new(game));
+                    child
+                }
+            };
+
+            self.nodes[node].edges.push(Edge {
+                game_move,
+                child,
+                visits: 0.0,
+            });
+        }
+    }
+
+    /// Refresh the state of wins/visits of the nodes on `path`, and the visits of the edges in `edge_indices`
+    /// linking them. Wins are counted from the perspective of the player who made the move leading to each node
+    /// along `path`, who may be the player to move in it
+    fn backpropagate(
+        &mut self,
+        path: &[NodeIndex],
+        edge_indices: &[usize],
+        game_result: GameOutcome<G::Player>,
+        config: &SearchConfig<G>,
+    ) {
+        for (path_index, &node) in path.iter().enumerate() {
+            let node_player = self.nodes[node].game.get_turn();
+            let repeats_turn =
+                path_index > 0 && self.nodes[path[path_index - 1]].game.get_turn() == node_player;
+            let reward = MCTree::<G>::reward(game_result, node_player, repeats_turn, config);
+
+            self.nodes[node].visits += 1.0;
+            self.nodes[node].wins += reward;
+            self.nodes[node].squared_rewards += reward * reward;
+        }
+
+        for (&node, &edge_index) in path.iter().zip(edge_indices.iter()) {
+            self.nodes[node].edges[edge_index].visits += 1.0;
+        }
+    }
+
+    /// Perform one round of an MCTS update. This includes:
+    /// 1- selecting a leaf node starting from root according to the tree policy
+    /// 2- expanding leaf node to include edges for its possible new moves
+    /// 3- simulating a random playout starting from the child of each new edge
+    /// 4- backpropagating game results of random playouts along the selected path and the new edge
+    ///
+    /// When the game is over at the leaf, its outcome is backpropagated instead
+    fn mcts_update<R: Rng + ?Sized>(&mut self, config: &SearchConfig<G>, rng: &mut R) {
+        let (mut path, mut edge_indices) = self.select_path(config, rng);
+        let leaf = *path.last().unwrap();
+
+        if let Some(game_result) = self.nodes[leaf].game.get_outcome() {
+            self.backpropagate(&path, &edge_indices, game_result, config);
+            return;
+        }
+
+        self.expand_node(leaf, config);
+
+        for edge_index in 0..self.nodes[leaf].edges.len() {
+            let child = self.nodes[leaf].edges[edge_index].child;
+            let (game_result, _) =
+                MCTree::<G>::simulate_playout(&self.nodes[child].game, config, rng);
+
+            path.push(child);
+            edge_indices.push(edge_index);
+            self.backpropagate(&path, &edge_indices, game_result, config);
+            path.pop();
+            edge_indices.pop();
+        }
+    }
+
+    /// Performes iterations of MCTS algorithm until the budget in `config` is used up, or one of its limits is hit,
+    /// and responds with best move found so far. A move is always returned unless the game is already over.
+    /// Moves are scored with the win rate of the node they lead to and the visits of their edge. Panics in debug
+    /// builds if `check_config` rejects `config`, and ignores the unsupported options in release builds
+    pub fn think_about_best_move(&mut self, config: &SearchConfig<G>) -> Option<G::Move> {
+        let mut rng = config.get_rng();
+        self.think_about_best_move_with_rng(config, &mut rng)
+    }
+
+    /// Same as `think_about_best_move`, but playouts draw from the caller's `rng`, and `config.seed` is ignored
+    pub fn think_about_best_move_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        config: &SearchConfig<G>,
+        rng: &mut R,
+    ) -> Option<G::Move> {
+        let supported = Self::check_config(config);
+        debug_assert!(
+            supported.is_ok(),
+            "Unsupported search config: {:?}",
+            supported
+        );
+
+        let start = time::Instant::now();
+        let mut iterations = 0;
+
+        while !MCTree::<G>::is_budget_used(config, start, iterations, self.get_node_count()) {
+            self.mcts_update(config, rng);
+            iterations += 1;
+        }
+
+        if self.nodes[ROOT].edges.is_empty() {
+            self.expand_node(ROOT, config);
+        }
+
+        // Give every move the win rate of its child node, but only count the visits that went through the move
+        let children: Vec<(G::Move, f64, f64)> = self.nodes[ROOT]
+            .edges
+            .iter()
+            .map(|edge| {
+                let win_rate = self.nodes[edge.child].get_win_rate(0.0);
+                (edge.game_move, win_rate * edge.visits, edge.visits)
+            })
+            .collect();
+
+        MCTree::<G>::choose_final_move(&children, self.nodes[ROOT].visits, config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_config::SearchBudget;
+    use crate::tree_policy::{ThompsonSampling, TreePolicy, Ucb1Tuned, UcbV};
+    use std::sync;
+    use tic_tac_toe::game;
+
+    fn iterations_config(iterations: u32) -> SearchConfig<game::Game> {
+        SearchConfig {
+            budget: SearchBudget::Iterations(iterations),
+            seed: Some(3),
+            ..Default::default()
+        }
+    }
+
+    /// Expands every node of `tree` up to `depth` moves away from the root
+    fn expand_to_depth(tree: &mut TranspositionTree<game::Game>, depth: usize) {
+        let mut frontier = vec![ROOT];
+
+        for _ in 0..depth {
+            let mut next_frontier = Vec::new();
+
+            for node in frontier {
+                if tree.nodes[node].edges.is_empty() {
+                    tree.expand_node(node, &iterations_config(0));
+                    next_frontier.extend(tree.nodes[node].edges.iter().map(|edge| edge.child));
+                }
+            }
+
+            frontier = next_frontier;
+        }
+    }
+
+    #[test]
+    fn test_expand_node_shares_transpositions() {
+        let mut tree = TranspositionTree::new(&game::Game::new());
+        expand_to_depth(&mut tree, 3);
+
+        // A plain tree holds 9 + 9 * 8 + 9 * 8 * 7 = 585 nodes below the root, one per edge. Positions after two
+        // moves are all distinct, but after three moves both X stones can be played in either order
+        assert_eq!(tree.get_edge_count(), 585);
+        assert_eq!(tree.get_node_count(), 1 + 9 + 72 + 36 * 7);
+        assert_eq!(tree.table.len(), tree.get_node_count());
+    }
+
+    #[test]
+    fn test_backpropagate_shared_node() {
+        let mut tree = TranspositionTree::new(&game::Game::new());
+        expand_to_depth(&mut tree, 3);
+
+        // X at (0, 0), O at (1, 1), X at (2, 2), and the same position with both X moves swapped
+        let find_edge = |tree: &TranspositionTree<game::Game>, node: NodeIndex, game_move| {
+            tree.nodes[node]
+                .edges
+                .iter()
+                .position(|edge| edge.game_move == game_move)
+                .unwrap()
+        };
+        let mut paths = Vec::new();
+        for moves in [[(0, 0), (1, 1), (2, 2)], [(2, 2), (1, 1), (0, 0)]] {
+            let mut path = vec![ROOT];
+            let mut edge_indices = Vec::new();
+            for game_move in moves {
+                let node = *path.last().unwrap();
+                let edge_index = find_edge(&tree, node, game_move);
+                edge_indices.push(edge_index);
+                path.push(tree.nodes[node].edges[edge_index].child);
+            }
+            paths.push((path, edge_indices));
+        }
+
+        let shared_node = *paths[0].0.last().unwrap();
+        assert_eq!(shared_node, *paths[1].0.last().unwrap());
+
+        let config = iterations_config(0);
+        for (path, edge_indices) in paths.iter() {
+            tree.backpropagate(
+                path,
+                edge_indices,
+                GameOutcome::Won(game::GameTurn::TurnX),
+                &config,
+            );
+        }
+
+        // The shared node collects the playouts of both paths, while each edge into it only counts its own
+        assert!((tree.nodes[shared_node].visits - 2.0).abs() < 1e-7);
+        assert!((tree.nodes[shared_node].wins - 2.0).abs() < 1e-7);
+        for (path, edge_indices) in paths.iter() {
+            let last_edge = &tree.nodes[path[2]].edges[edge_indices[2]];
+            assert!((last_edge.visits - 1.0).abs() < 1e-7);
+        }
+        assert!((tree.get_root_visits() - 2.0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_think_about_best_move_fewer_nodes_than_tree() {
+        let config = iterations_config(2000);
+
+        let mut dag = TranspositionTree::new(&game::Game::new());
+        let dag_move = dag.think_about_best_move(&config);
+
+        let mut tree = MCTree::new(&game::Game::new());
+        tree.think_about_best_move(&config);
+
+        assert!(game::Game::new()
+            .get_possible_plays()
+            .contains(&dag_move.unwrap()));
+        assert!(dag.get_node_count() < tree.get_node_count());
+    }
+
+    #[test]
+    fn test_think_about_best_move_game_over() {
+        let mut game = game::Game::new();
+        for (row_index, col_index) in [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)] {
+            game.play(row_index, col_index).unwrap();
+        }
+
+        let mut tree = TranspositionTree::new(&game);
+        assert_eq!(tree.think_about_best_move(&iterations_config(10)), None);
+        assert!((tree.get_root_visits() - 10.0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_check_config() {
+        assert_eq!(
+            TranspositionTree::check_config(&iterations_config(10)),
+            Ok(())
+        );
+
+        let config = SearchConfig {
+            solver: true,
+            ..iterations_config(10)
+        };
+        assert_eq!(
+            TranspositionTree::check_config(&config),
+            Err(SearchConfigError::UnsupportedSolver)
+        );
+
+        let config = SearchConfig {
+            expansion: ExpansionMode::Single { threshold: 1 },
+            ..iterations_config(10)
+        };
+        assert_eq!(
+            TranspositionTree::check_config(&config),
+            Err(SearchConfigError::UnsupportedExpansion)
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Unsupported search config")]
+    fn test_think_about_best_move_rejects_rave() {
+        let config = SearchConfig {
+            rave_equivalence: Some(100.0),
+            ..iterations_config(10)
+        };
+
+        TranspositionTree::new(&game::Game::new()).think_about_best_move(&config);
+    }
+
+    #[test]
+    fn test_think_about_best_move_merge_symmetries() {
+        let config = SearchConfig {
+            merge_symmetries: true,
+            ..iterations_config(100)
+        };
+
+        // Corner, edge and centre openings are the only distinct moves of the empty board
+        let mut tree = TranspositionTree::new(&game::Game::new());
+        let best_move = tree.think_about_best_move(&config);
+
+        assert_eq!(tree.nodes[ROOT].edges.len(), 3);
+        assert!([(0, 0), (0, 1), (1, 1)].contains(&best_move.unwrap()));
+    }
+
+    #[test]
+    fn test_think_about_best_move_tree_policy() {
+        // X to move wins at (0, 2), which every tree policy finds
+        let mut game = game::Game::new();
+        for (row_index, col_index) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            game.play(row_index, col_index).unwrap();
+        }
+
+        let tree_policies: [sync::Arc<dyn TreePolicy>; 3] = [
+            sync::Arc::new(Ucb1Tuned),
+            sync::Arc::new(UcbV::default()),
+            sync::Arc::new(ThompsonSampling),
+        ];
+        for tree_policy in tree_policies {
+            let config = SearchConfig {
+                tree_policy: Some(tree_policy),
+                ..iterations_config(300)
+            };
+
+            let mut tree = TranspositionTree::new(&game);
+            assert_eq!(tree.think_about_best_move(&config), Some((0, 2)));
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Contains exporters writing an MCTS (Monte Carlo Tree Search) tree in Graphviz DOT and JSON formats
+//!
+//! Exports start at the root and can be cut off below a given depth, or at nodes with too few visits, which keeps
+//! large trees readable. Games are shown through their `Display` implementation, and moves through their `Debug`
+//! implementation.
+//!
+//! ## Examples
+//!
+//! ```
+//! use mcts::{mcts_core, search_config, tree_export};
+//! use tic_tac_toe::game;
+//!
+//! let config = search_config::SearchConfig {
+//!     budget: search_config::SearchBudget::Iterations(100),
+//!     ..Default::default()
+//! };
+//!
+//! let mut tree = mcts_core::MCTree::new(&game::Game::new());
+//! tree.think_about_best_move(&config);
+//!
+//! let options = tree_export::ExportOptions {
+//!     max_depth: Some(2),
+//!     min_visits: 10.0,
+//! };
+//! let dot = tree_export::to_dot(&tree, &options);
+//! assert!(dot.starts_with("digraph mcts {"));
+//!
+//! let json = tree_export::to_json(&tree, &options);
+//! assert!(json.starts_with('{'));
+//! ```
+
+use crate::mcts_core::{MCTree, NodeIndex, ROOT};
+use crate::mcts_game::MCTSGame;
+use std::fmt::{self, Write};
+
+/// Represents the cutoffs of an export. The root is always exported. Includes
+/// - depth below which nodes are left out, the root being at depth 0 (None exports every depth)
+/// - number of visits under which nodes are left out, together with their subtrees
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ExportOptions {
+    pub max_depth: Option<usize>,
+    pub min_visits: f64,
+}
+
+/// Gets the children of `node` at `depth` that pass the cutoffs in `options`
+fn get_exported_children<G: MCTSGame>(
+    tree: &MCTree<G>,
+    node: NodeIndex,
+    depth: usize,
+    options: &ExportOptions,
+) -> Vec<NodeIndex> {
+    if options
+        .max_depth
+        .is_some_and(|max_depth| depth >= max_depth)
+    {
+        return Vec::new();
+    }
+
+    tree.get_node(node)
+        .get_children()
+        .iter()
+        .copied()
+        .filter(|&child| tree.get_node(child).get_visits() >= options.min_visits)
+        .collect()
+}
+
+/// Gets the win rate of `node` for the player who made the move leading to it, or None if it was never visited
+fn get_win_rate<G: MCTSGame>(tree: &MCTree<G>, node: NodeIndex) -> Option<f64> {
+    let node = tree.get_node(node);
+
+    if node.get_visits() > 0.0 {
+        Some(node.get_wins() / node.get_visits())
+    } else {
+        None
+    }
+}
+
+/// Escapes `text` to be used inside a double-quoted DOT string, with its lines left-aligned
+fn escape_dot(text: &str) -> String {
+    let mut escaped = String::new();
+
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        escaped.push_str(&line.replace('\\', "\\\\").replace('"', "\\\""));
+        escaped.push_str("\\l");
+    }
+
+    escaped
+}
+
+/// Writes the DOT statements of `node` at `depth` and of its exported subtree to `dot`
+fn write_dot_node<G: MCTSGame + fmt::Display>(
+    tree: &MCTree<G>,
+    node: NodeIndex,
+    depth: usize,
+    options: &ExportOptions,
+    dot: &mut String,
+) {
+    let mctn = tree.get_node(node);
+    let mut label = format!("visits: {}\n", mctn.get_visits());
+
+    match get_win_rate(tree, node) {
+        Some(win_rate) => label.push_str(&format!("win rate: {:.3}\n", win_rate)),
+        None => label.push_str("win rate: -\n"),
+    }
+
+    if let Some(proven) = mctn.get_proven() {
+        label.push_str(&format!("proven: {:?}\n", proven));
+    }
+
+    label.push_str(&mctn.get_game().to_string());
+    writeln!(dot, "    n{} [label=\"{}\"];", node, escape_dot(&label)).unwrap();
+
+    for child in get_exported_children(tree, node, depth, options) {
+        let child_move = tree.get_node(child).get_move_from_parent().unwrap();
+        writeln!(
+            dot,
+            "    n{} -> n{} [label=\"{}\"];",
+            node,
+            child,
+            format!("{:?}", child_move).replace('"', "\\\"")
+        )
+        .unwrap();
+
+        write_dot_node(tree, child, depth + 1, options, dot);
+    }
+}
+
+/// Exports `tree` as a Graphviz DOT digraph, with one box per node showing its visits, its win rate for the
+/// player who made the move leading to it, its proven outcome if known, and its game. Edges are labelled with moves
+pub fn to_dot<G: MCTSGame + fmt::Display>(tree: &MCTree<G>, options: &ExportOptions) -> String {
+    let mut dot = String::from("digraph mcts {\n");
+    dot.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+
+    write_dot_node(tree, ROOT, 0, options, &mut dot);
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Escapes `text` as a JSON string, quotes included
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::from("\"");
+
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            character if (character as u32) < 0x20 => {
+                escaped.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => escaped.push(character),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+/// Writes `node` at `depth` and its exported subtree to `json` as a JSON object
+fn write_json_node<G: MCTSGame + fmt::Display>(
+    tree: &MCTree<G>,
+    node: NodeIndex,
+    depth: usize,
+    options: &ExportOptions,
+    json: &mut String,
+) {
+    let mctn = tree.get_node(node);
+    let game_move = match mctn.get_move_from_parent() {
+        Some(game_move) => escape_json(&format!("{:?}", game_move)),
+        None => "null".to_string(),
+    };
+    let win_rate = match get_win_rate(tree, node) {
+        Some(win_rate) => win_rate.to_string(),
+        None => "null".to_string(),
+    };
+    let proven = match mctn.get_proven() {
+        Some(proven) => escape_json(&format!("{:?}", proven)),
+        None => "null".to_string(),
+    };
+
+    write!(
+        json,
+        "{{\"move\":{},\"visits\":{},\"wins\":{},\"win_rate\":{},\"proven\":{},\"game\":{},\"children\":[",
+        game_move,
+        mctn.get_visits(),
+        mctn.get_wins(),
+        win_rate,
+        proven,
+        escape_json(&mctn.get_game().to_string())
+    )
+    .unwrap();
+
+    for (child_index, child) in get_exported_children(tree, node, depth, options)
+        .into_iter()
+        .enumerate()
+    {
+        if child_index > 0 {
+            json.push(',');
+        }
+        write_json_node(tree, child, depth + 1, options, json);
+    }
+
+    json.push_str("]}");
+}
+
+/// Exports `tree` as nested JSON objects, one per node, with the fields
+/// - `move`: move leading to the node, or null for the root
+/// - `visits`: number of visits
+/// - `wins`: sum of rewards, for the player who made the move leading to the node
+/// - `win_rate`: wins per visit, or null if the node was never visited
+/// - `proven`: outcome the node is proven to reach, or null if unknown
+/// - `game`: game state of the node
+/// - `children`: exported children of the node
+pub fn to_json<G: MCTSGame + fmt::Display>(tree: &MCTree<G>, options: &ExportOptions) -> String {
+    let mut json = String::new();
+    write_json_node(tree, ROOT, 0, options, &mut json);
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_config::{SearchBudget, SearchConfig};
+    use tic_tac_toe::game;
+
+    fn searched_tree() -> MCTree<game::Game> {
+        let config = SearchConfig {
+            budget: SearchBudget::Iterations(100),
+            seed: Some(0),
+            ..Default::default()
+        };
+        let mut tree = MCTree::new(&game::Game::new());
+        tree.think_about_best_move(&config);
+        tree
+    }
+
+    #[test]
+    fn test_escape_dot() {
+        assert_eq!(escape_dot("a \"b\"\n\nc\\d\n"), "a \\\"b\\\"\\lc\\\\d\\l");
+    }
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(
+            escape_json("a \"b\"\n\tc\\d\u{1}"),
+            "\"a \\\"b\\\"\\n\\tc\\\\d\\u0001\""
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let tree = searched_tree();
+        let dot = to_dot(&tree, &ExportOptions::default());
+
+        assert!(dot.starts_with("digraph mcts {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert_eq!(
+            dot.matches(" [label=").count(),
+            2 * tree.get_node_count() - 1
+        );
+        assert!(dot.contains("n0 -> "));
+        assert!(dot.contains(&format!("visits: {}", tree.get_root_visits())));
+    }
+
+    #[test]
+    fn test_to_dot_depth_cutoff() {
+        let tree = searched_tree();
+        let options = ExportOptions {
+            max_depth: Some(1),
+            ..Default::default()
+        };
+        let dot = to_dot(&tree, &options);
+
+        // The root and its 9 children, with an edge to each child
+        assert_eq!(dot.matches(" [label=").count(), 1 + 2 * 9);
+    }
+
+    #[test]
+    fn test_to_json_visit_cutoff() {
+        let tree = searched_tree();
+        // Keep only the most visited children of the root
+        let min_visits = tree
+            .get_root_child_visits()
+            .iter()
+            .map(|&(_, visits)| visits)
+            .fold(0.0, f64::max);
+        let options = ExportOptions {
+            max_depth: Some(1),
+            min_visits,
+        };
+        let json = to_json(&tree, &options);
+
+        let kept_children = tree
+            .get_root_child_visits()
+            .iter()
+            .filter(|&&(_, visits)| visits >= min_visits)
+            .count();
+        assert!((1..9).contains(&kept_children));
+        assert_eq!(json.matches("\"move\":").count(), 1 + kept_children);
+        assert!(json.starts_with("{\"move\":null,"));
+        assert!(json.ends_with("]}"));
+    }
+
+    #[test]
+    fn test_to_json_unvisited_root() {
+        let tree = MCTree::new(&game::Game::new());
+        let json = to_json(&tree, &ExportOptions::default());
+
+        assert!(json.contains("\"visits\":0,"));
+        assert!(json.contains("\"win_rate\":null,"));
+        assert!(json.contains("\"proven\":null,"));
+        assert!(json.ends_with("\"children\":[]}"));
+    }
+}
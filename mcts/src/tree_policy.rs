@@ -0,0 +1,287 @@
+//! Contains the policies that pick which child to descend into during MCTS (Monte Carlo Tree Search) selection
+//!
+//! Selection treats the children of every node as the arms of a multi-armed bandit. Every policy scores each
+//! child from its statistics, and the child with the highest score is selected. UCT is the classic choice,
+//! PUCT adds the priors of an evaluator, UCB1-Tuned and UCB-V shrink exploration for children whose rewards
+//! vary little, and Thompson sampling explores by drawing a plausible win rate for every child.
+//!
+//! ## Examples
+//!
+//! ```
+//! use mcts::{mcts_core, search_config, tree_policy};
+//! use std::sync;
+//! use tic_tac_toe::game;
+//!
+//! let config = search_config::SearchConfig {
+//!     tree_policy: Some(sync::Arc::new(tree_policy::Ucb1Tuned)),
+//!     budget: search_config::SearchBudget::Iterations(200),
+//!     ..Default::default()
+//! };
+//!
+//! let mut tree = mcts_core::MCTree::new(&game::Game::new());
+//! assert!(tree.think_about_best_move(&config).is_some());
+//! ```
+
+use rand::RngCore;
+use rand_distr::{Beta, Distribution};
+
+/// Represents the statistics of a child that a tree policy scores. Rewards are those of the player to move at the
+/// parent. Includes
+/// - estimated win rate, blended with the AMAF (All-Moves-As-First) win rate when RAVE is enabled. For a child
+///   that was never visited, the value of the parent for the player to move there
+/// - sum of rewards
+/// - sum of squared rewards
+/// - number of visits
+/// - prior probability given by an evaluator (0 without one)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChildStats {
+    pub value: f64,
+    pub wins: f64,
+    pub squared_rewards: f64,
+    pub visits: f64,
+    pub prior: f64,
+}
+
+impl ChildStats {
+    /// Gets the empirical variance of the rewards of the child, or 0 if it was never visited
+    pub fn get_variance(&self) -> f64 {
+        if self.visits == 0.0 {
+            return 0.0;
+        }
+
+        let mean = self.wins / self.visits;
+        (self.squared_rewards / self.visits - mean * mean).max(0.0)
+    }
+}
+
+/// Represents a policy scoring the children of a node during selection
+pub trait TreePolicy: Send + Sync {
+    /// Scores `child`, one of the children of a node with `parent_visits` visits. `exploration_constant` comes
+    /// from the search config, and policies that do not need it ignore it
+    fn score(
+        &self,
+        parent_visits: f64,
+        child: &ChildStats,
+        exploration_constant: f64,
+        rng: &mut dyn RngCore,
+    ) -> f64;
+}
+
+/// Scores children with UCT (Upper Confidence Bound for Trees): value + C * sqrt(ln(N) / n). Children that were
+/// never visited score infinity
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Uct;
+
+impl TreePolicy for Uct {
+    fn score(
+        &self,
+        parent_visits: f64,
+        child: &ChildStats,
+        exploration_constant: f64,
+        _rng: &mut dyn RngCore,
+    ) -> f64 {
+        if child.visits == 0.0 {
+            return f64::INFINITY;
+        }
+
+        child.value + exploration_constant * (parent_visits.ln() / child.visits).sqrt()
+    }
+}
+
+/// Scores children with PUCT (Predictor + Upper Confidence bounds applied to Trees):
+/// value + C * prior * sqrt(N) / (1 + n). Only useful with an evaluator giving priors
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Puct;
+
+impl TreePolicy for Puct {
+    fn score(
+        &self,
+        parent_visits: f64,
+        child: &ChildStats,
+        exploration_constant: f64,
+        _rng: &mut dyn RngCore,
+    ) -> f64 {
+        child.value
+            + exploration_constant * child.prior * parent_visits.sqrt() / (1.0 + child.visits)
+    }
+}
+
+/// Scores children with UCB1-Tuned: value + sqrt(ln(N) / n * min(1/4, V)), where V is the variance of the rewards
+/// of the child plus sqrt(2 * ln(N) / n). 1/4 is the largest variance of a reward between 0 and 1. Ignores the
+/// exploration constant. Children that were never visited score infinity
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ucb1Tuned;
+
+impl TreePolicy for Ucb1Tuned {
+    fn score(
+        &self,
+        parent_visits: f64,
+        child: &ChildStats,
+        _exploration_constant: f64,
+        _rng: &mut dyn RngCore,
+    ) -> f64 {
+        if child.visits == 0.0 {
+            return f64::INFINITY;
+        }
+
+        let log_ratio = parent_visits.ln() / child.visits;
+        let variance_bound = child.get_variance() + (2.0 * log_ratio).sqrt();
+
+        child.value + (log_ratio * variance_bound.min(0.25)).sqrt()
+    }
+}
+
+/// Scores children with UCB-V: value + sqrt(2 * V * E / n) + c * 3 * E / n, where V is the variance of the rewards
+/// of the child and E = zeta * ln(N) is the exploration function. Ignores the exploration constant. Children that
+/// were never visited score infinity
+#[derive(Debug, Clone, Copy)]
+pub struct UcbV {
+    pub zeta: f64,
+    pub c: f64,
+}
+
+impl Default for UcbV {
+    fn default() -> Self {
+        UcbV { zeta: 1.2, c: 1.0 }
+    }
+}
+
+impl TreePolicy for UcbV {
+    fn score(
+        &self,
+        parent_visits: f64,
+        child: &ChildStats,
+        _exploration_constant: f64,
+        _rng: &mut dyn RngCore,
+    ) -> f64 {
+        if child.visits == 0.0 {
+            return f64::INFINITY;
+        }
+
+        let exploration = self.zeta * parent_visits.ln();
+
+        child.value
+            + (2.0 * child.get_variance() * exploration / child.visits).sqrt()
+            + self.c * 3.0 * exploration / child.visits
+    }
+}
+
+/// Scores children with Beta-Bernoulli Thompson sampling: a win rate drawn from Beta(1 + wins, 1 + losses),
+/// starting from a uniform prior. Ties count as partial wins. Ignores the exploration constant and the RAVE
+/// blend of the value
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThompsonSampling;
+
+impl TreePolicy for ThompsonSampling {
+    fn score(
+        &self,
+        _parent_visits: f64,
+        child: &ChildStats,
+        _exploration_constant: f64,
+        rng: &mut dyn RngCore,
+    ) -> f64 {
+        let losses = (child.visits - child.wins).max(0.0);
+        let beta = Beta::new(1.0 + child.wins, 1.0 + losses).unwrap();
+
+        beta.sample(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs, SeedableRng};
+
+    fn child_stats(wins: f64, squared_rewards: f64, visits: f64) -> ChildStats {
+        ChildStats {
+            value: wins / visits,
+            wins,
+            squared_rewards,
+            visits,
+            prior: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_get_variance() {
+        // Rewards 1, 0, 1, 0 have mean 0.5 and variance 0.25
+        assert!((child_stats(2.0, 2.0, 4.0).get_variance() - 0.25).abs() < 1e-7);
+        // Rewards 0.5, 0.5 have no variance
+        assert!((child_stats(1.0, 0.5, 2.0).get_variance() - 0.0).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_uct_and_puct_scores() {
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let child = ChildStats {
+            prior: 0.5,
+            ..child_stats(3.0, 3.0, 4.0)
+        };
+
+        let uct = Uct.score(10.0, &child, 2.0, &mut rng);
+        assert!((uct - (0.75 + 2.0 * (10.0_f64.ln() / 4.0).sqrt())).abs() < 1e-7);
+
+        let puct = Puct.score(16.0, &child, 2.0, &mut rng);
+        assert!((puct - (0.75 + 2.0 * 0.5 * 4.0 / 5.0)).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_variance_aware_policies_prefer_uncertain_children() {
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        // Same win rate, but the first child always draws while the second one alternates wins and losses
+        let steady_child = child_stats(1000.0, 500.0, 2000.0);
+        let uncertain_child = child_stats(1000.0, 1000.0, 2000.0);
+
+        for policy in [&Ucb1Tuned as &dyn TreePolicy, &UcbV::default()] {
+            let steady_score = policy.score(10000.0, &steady_child, 0.0, &mut rng);
+            let uncertain_score = policy.score(10000.0, &uncertain_child, 0.0, &mut rng);
+            assert!(uncertain_score > steady_score);
+        }
+    }
+
+    #[test]
+    fn test_unvisited_children_score_infinity() {
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let child = ChildStats {
+            value: 0.5,
+            wins: 0.0,
+            squared_rewards: 0.0,
+            visits: 0.0,
+            prior: 0.0,
+        };
+
+        assert_eq!(Ucb1Tuned.score(10.0, &child, 0.0, &mut rng), f64::INFINITY);
+
+        // Also when the parent has at most one visit, as happens with single expansion or an evaluator
+        for parent_visits in [0.0, 1.0, 10.0] {
+            assert_eq!(
+                Uct.score(parent_visits, &child, 2.0, &mut rng),
+                f64::INFINITY
+            );
+        }
+        assert_eq!(
+            UcbV::default().score(10.0, &child, 0.0, &mut rng),
+            f64::INFINITY
+        );
+    }
+
+    #[test]
+    fn test_thompson_sampling_follows_win_rate() {
+        let mut rng = rngs::StdRng::seed_from_u64(0);
+        let winning_child = child_stats(90.0, 90.0, 100.0);
+        let losing_child = child_stats(10.0, 10.0, 100.0);
+
+        let samples = 1000;
+        let mut total = 0.0;
+        for _ in 0..samples {
+            let winning_score = ThompsonSampling.score(200.0, &winning_child, 0.0, &mut rng);
+            let losing_score = ThompsonSampling.score(200.0, &losing_child, 0.0, &mut rng);
+            assert!((0.0..=1.0).contains(&winning_score));
+            assert!(winning_score > losing_score);
+            total += winning_score;
+        }
+
+        // The mean of Beta(91, 11) is 91 / 102
+        assert!((total / samples as f64 - 91.0 / 102.0).abs() < 0.01);
+    }
+}
@@ -1,19 +1,100 @@
 //! Contains functionality for manipulating a Tic-Tac-Toe board
+//!
+//! Boards default to the classic 3x3 grid, but can have any width and height, as used by m,n,k-games where
+//! players try to get k marks in a row on an m by n board.
 
 use std::fmt;
 
 /// Represents a Tic-Tac-Toe Cell
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Cell {
     X,
     O,
     Empty,
 }
 
-/// Represents a 3x3 Tic-Tac-Toe board
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Represents a Tic-Tac-Toe board with `height` rows and `width` columns. Cells are stored row by row
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Board {
-    cells: [[Cell; 3]; 3],
+    height: usize,
+    width: usize,
+    cells: Vec<Cell>,
+}
+
+/// Directions of the lines going through a cell: horizontal, vertical, diagonal and anti-diagonal
+pub const LINE_DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// Represents one of the 8 symmetries of a square board (the dihedral group D4): 4 rotations, counted
+/// clockwise, and 4 reflections. Only the identity, the half turn and the left-right and top-bottom reflections
+/// keep the shape of a board that is not square
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    ReflectLeftRight,
+    ReflectTopBottom,
+    ReflectMainDiagonal,
+    ReflectAntiDiagonal,
+}
+
+impl Symmetry {
+    /// All the symmetries of the board, starting with the identity
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::ReflectLeftRight,
+        Symmetry::ReflectTopBottom,
+        Symmetry::ReflectMainDiagonal,
+        Symmetry::ReflectAntiDiagonal,
+    ];
+
+    /// Returns the location that the cell at location (`row_index`, `col_index`) of a board with `height` rows
+    /// and `width` columns is moved to by the symmetry
+    pub fn map_location(
+        self,
+        row_index: usize,
+        col_index: usize,
+        height: usize,
+        width: usize,
+    ) -> (usize, usize) {
+        let last_row = height - 1;
+        let last_col = width - 1;
+
+        match self {
+            Symmetry::Identity => (row_index, col_index),
+            Symmetry::Rotate90 => (col_index, last_row - row_index),
+            Symmetry::Rotate180 => (last_row - row_index, last_col - col_index),
+            Symmetry::Rotate270 => (last_col - col_index, row_index),
+            Symmetry::ReflectLeftRight => (row_index, last_col - col_index),
+            Symmetry::ReflectTopBottom => (last_row - row_index, col_index),
+            Symmetry::ReflectMainDiagonal => (col_index, row_index),
+            Symmetry::ReflectAntiDiagonal => (last_col - col_index, last_row - row_index),
+        }
+    }
+
+    /// Returns a boolean indicating whether the symmetry keeps the shape of any board, square or not
+    pub fn preserves_shape(self) -> bool {
+        matches!(
+            self,
+            Symmetry::Identity
+                | Symmetry::Rotate180
+                | Symmetry::ReflectLeftRight
+                | Symmetry::ReflectTopBottom
+        )
+    }
+
+    /// Returns the symmetry undoing this one
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            symmetry => symmetry,
+        }
+    }
 }
 
 /// Represents the possible reasons when failing to mark a board cell
@@ -24,18 +105,43 @@ pub enum BoardMarkError {
 }
 
 impl Board {
-    /// Constructs a new Tic-Tac-Toe `Board`
+    /// Constructs a new 3x3 Tic-Tac-Toe `Board`
     pub fn new() -> Self {
+        Board::with_size(3, 3)
+    }
+
+    /// Constructs a new empty `Board` with `height` rows and `width` columns
+    pub fn with_size(height: usize, width: usize) -> Self {
         Board {
-            cells: [[Cell::Empty; 3]; 3],
+            height,
+            width,
+            cells: vec![Cell::Empty; width * height],
+        }
+    }
+
+    /// Gets the number of columns of the board
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    /// Gets the number of rows of the board
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the index in `cells` of location (`row_index`, `col_index`), or None if location is out-of-bound
+    fn get_index(&self, row_index: usize, col_index: usize) -> Option<usize> {
+        if row_index < self.height && col_index < self.width {
+            Some(row_index * self.width + col_index)
+        } else {
+            None
         }
     }
 
     /// Returns `Cell` at location (`row_index`, `col_index`), or an `Err` if location is out-of-bound
     pub fn get_cell(&self, row_index: usize, col_index: usize) -> Result<Cell, &'static str> {
-        self.cells
-            .get(row_index)
-            .and_then(|r| r.get(col_index).copied())
+        self.get_index(row_index, col_index)
+            .map(|index| self.cells[index])
             .ok_or("Board index out of bound.")
     }
 
@@ -48,14 +154,10 @@ impl Board {
         row_index: usize,
         col_index: usize,
     ) -> Result<(), BoardMarkError> {
-        match self
-            .cells
-            .get_mut(row_index)
-            .and_then(|r| r.get_mut(col_index))
-        {
-            Some(cell) => match cell {
+        match self.get_index(row_index, col_index) {
+            Some(index) => match self.cells[index] {
                 Cell::Empty => {
-                    *cell = mark;
+                    self.cells[index] = mark;
                     Ok(())
                 }
                 _ => Err(BoardMarkError::NonEmptyCell),
@@ -63,27 +165,132 @@ impl Board {
             None => Err(BoardMarkError::OutOfBound),
         }
     }
+
+    /// Returns a boolean indicating whether every cell of the board is marked
+    pub fn is_full(&self) -> bool {
+        !self.cells.contains(&Cell::Empty)
+    }
+
+    /// Returns, for each of the `LINE_DIRECTIONS`, the number of cells with the same mark as the cell at location
+    /// (`row_index`, `col_index`) in the unbroken line going through it along that direction. Returns all 0 for an
+    /// empty or out-of-bound cell
+    pub fn get_line_lengths(&self, row_index: usize, col_index: usize) -> [usize; 4] {
+        let mark = match self.get_cell(row_index, col_index) {
+            Ok(Cell::Empty) | Err(_) => return [0; 4],
+            Ok(mark) => mark,
+        };
+
+        let count_towards = |row_step: isize, col_step: isize| {
+            let mut count = 0;
+            let (mut row, mut col) = (row_index as isize, col_index as isize);
+
+            loop {
+                row += row_step;
+                col += col_step;
+                if row < 0 || col < 0 {
+                    return count;
+                }
+
+                match self.get_cell(row as usize, col as usize) {
+                    Ok(cell) if cell == mark => count += 1,
+                    _ => return count,
+                }
+            }
+        };
+
+        LINE_DIRECTIONS.map(|(row_step, col_step)| {
+            1 + count_towards(row_step, col_step) + count_towards(-row_step, -col_step)
+        })
+    }
+
+    /// Returns the number of cells with the same mark as the cell at location (`row_index`, `col_index`) in the
+    /// longest unbroken horizontal, vertical or diagonal line going through it. Returns 0 for an empty or
+    /// out-of-bound cell
+    pub fn get_longest_line(&self, row_index: usize, col_index: usize) -> usize {
+        self.get_line_lengths(row_index, col_index)
+            .into_iter()
+            .max()
+            .unwrap()
+    }
+
+    /// Gets the symmetries that keep the shape of the board: all 8 of them for a square board, and the 4 that
+    /// preserve shape otherwise
+    pub fn get_symmetries(&self) -> Vec<Symmetry> {
+        Symmetry::ALL
+            .into_iter()
+            .filter(|symmetry| self.width == self.height || symmetry.preserves_shape())
+            .collect()
+    }
+
+    /// Returns a copy of the board with every cell moved by `symmetry`. Symmetries that do not preserve shape
+    /// swap the width and height of the board
+    pub fn get_transformed(&self, symmetry: Symmetry) -> Board {
+        let mut transformed = if symmetry.preserves_shape() {
+            Board::with_size(self.height, self.width)
+        } else {
+            Board::with_size(self.width, self.height)
+        };
+
+        for row_index in 0..self.height {
+            for col_index in 0..self.width {
+                let (new_row_index, new_col_index) =
+                    symmetry.map_location(row_index, col_index, self.height, self.width);
+                let new_index = transformed.get_index(new_row_index, new_col_index).unwrap();
+                transformed.cells[new_index] = self.cells[row_index * self.width + col_index];
+            }
+        }
+
+        transformed
+    }
+
+    /// Returns a copy of the board rotated clockwise by a quarter turn
+    pub fn get_rotated(&self) -> Board {
+        self.get_transformed(Symmetry::Rotate90)
+    }
+
+    /// Returns a copy of the board mirrored left to right
+    pub fn get_reflected(&self) -> Board {
+        self.get_transformed(Symmetry::ReflectLeftRight)
+    }
+
+    /// Returns the canonical form of the board, i.e. the smallest of its symmetric images of the same shape,
+    /// together with the symmetry that maps the board onto it. Boards are symmetric to each other if and only if
+    /// they share the same canonical form. Locations on the board map to the canonical form through the returned
+    /// symmetry, and back through its inverse
+    pub fn get_canonical(&self) -> (Board, Symmetry) {
+        self.get_symmetries()
+            .into_iter()
+            .map(|symmetry| (self.get_transformed(symmetry), symmetry))
+            .min_by(|(first, _), (second, _)| first.cmp(second))
+            .unwrap()
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Board::new()
+    }
 }
 
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row_index in 0..=2 {
-            for col_index in 0..=2 {
-                let symbol = match self.cells[row_index][col_index] {
+        for row_index in 0..self.height {
+            for col_index in 0..self.width {
+                let symbol = match self.cells[row_index * self.width + col_index] {
                     Cell::X => "X",
                     Cell::O => "O",
                     Cell::Empty => " ",
                 };
 
-                if col_index < 2 {
+                if col_index + 1 < self.width {
                     write!(f, " {} |", symbol)?;
                 } else {
                     write!(f, " {} ", symbol)?;
                 }
             }
 
-            if row_index < 2 {
-                writeln!(f, "\n-----------")?;
+            if row_index + 1 < self.height {
+                writeln!(f, "\n{}", "-".repeat(4 * self.width - 1))?;
             }
         }
         Ok(())
@@ -99,7 +306,7 @@ mod tests {
         let b = Board::new();
         for row_index in 0..=2 {
             for col_index in 0..=2 {
-                assert_eq!(b.cells[row_index][col_index], Cell::Empty);
+                assert_eq!(b.get_cell(row_index, col_index).unwrap(), Cell::Empty);
             }
         }
     }
@@ -108,7 +315,7 @@ mod tests {
     fn test_mark_board() {
         let mut b = Board::new();
         b.mark(Cell::X, 0, 0).unwrap();
-        assert_eq!(b.cells[0][0], Cell::X);
+        assert_eq!(b.get_cell(0, 0).unwrap(), Cell::X);
     }
 
     #[test]
@@ -125,4 +332,151 @@ mod tests {
         let result = b.mark(Cell::O, 0, 0);
         assert_eq!(result, Err(BoardMarkError::NonEmptyCell));
     }
+
+    #[test]
+    fn test_rotate_board() {
+        let mut b = Board::new();
+        b.mark(Cell::X, 0, 0).unwrap();
+        b.mark(Cell::O, 0, 1).unwrap();
+
+        let rotated = b.get_rotated();
+        assert_eq!(rotated.get_cell(0, 2).unwrap(), Cell::X);
+        assert_eq!(rotated.get_cell(1, 2).unwrap(), Cell::O);
+
+        // Four quarter turns bring the board back
+        assert_eq!(rotated.get_rotated().get_rotated().get_rotated(), b);
+    }
+
+    #[test]
+    fn test_reflect_board() {
+        let mut b = Board::new();
+        b.mark(Cell::X, 1, 0).unwrap();
+
+        let reflected = b.get_reflected();
+        assert_eq!(reflected.get_cell(1, 2).unwrap(), Cell::X);
+        assert_eq!(reflected.get_reflected(), b);
+    }
+
+    #[test]
+    fn test_symmetry_inverse() {
+        for symmetry in Symmetry::ALL {
+            for row_index in 0..=2 {
+                for col_index in 0..=2 {
+                    let (new_row_index, new_col_index) =
+                        symmetry.map_location(row_index, col_index, 3, 3);
+                    assert_eq!(
+                        symmetry
+                            .inverse()
+                            .map_location(new_row_index, new_col_index, 3, 3),
+                        (row_index, col_index)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonical_board() {
+        let mut canonical_openings = Vec::new();
+
+        for row_index in 0..=2 {
+            for col_index in 0..=2 {
+                let mut b = Board::new();
+                b.mark(Cell::X, row_index, col_index).unwrap();
+
+                let (canonical, symmetry) = b.get_canonical();
+                assert_eq!(b.get_transformed(symmetry), canonical);
+
+                if !canonical_openings.contains(&canonical) {
+                    canonical_openings.push(canonical);
+                }
+            }
+        }
+
+        // Corner, edge and centre openings
+        assert_eq!(canonical_openings.len(), 3);
+    }
+
+    #[test]
+    fn test_board_with_size() {
+        let mut b = Board::with_size(2, 4);
+        assert_eq!(b.get_width(), 4);
+        assert_eq!(b.get_height(), 2);
+
+        b.mark(Cell::X, 1, 3).unwrap();
+        assert_eq!(b.get_cell(1, 3).unwrap(), Cell::X);
+        assert_eq!(b.mark(Cell::O, 2, 0), Err(BoardMarkError::OutOfBound));
+        assert_eq!(b.mark(Cell::O, 0, 4), Err(BoardMarkError::OutOfBound));
+    }
+
+    #[test]
+    fn test_is_full() {
+        let mut b = Board::with_size(1, 2);
+        b.mark(Cell::X, 0, 0).unwrap();
+        assert!(!b.is_full());
+
+        b.mark(Cell::O, 0, 1).unwrap();
+        assert!(b.is_full());
+    }
+
+    #[test]
+    fn test_longest_line() {
+        let mut b = Board::with_size(5, 5);
+        for (row_index, col_index) in [(0, 0), (1, 1), (2, 2), (2, 3), (2, 4)] {
+            b.mark(Cell::X, row_index, col_index).unwrap();
+        }
+        b.mark(Cell::O, 3, 3).unwrap();
+
+        // (2, 2) lies on a diagonal of 3 and a row of 3, and the diagonal is cut by O at (3, 3)
+        assert_eq!(b.get_longest_line(2, 2), 3);
+        assert_eq!(b.get_longest_line(2, 4), 3);
+        assert_eq!(b.get_longest_line(3, 3), 1);
+        assert_eq!(b.get_longest_line(4, 4), 0);
+        assert_eq!(b.get_longest_line(5, 0), 0);
+    }
+
+    #[test]
+    fn test_line_lengths() {
+        let mut b = Board::with_size(7, 7);
+        for col_index in 0..6 {
+            b.mark(Cell::X, 3, col_index).unwrap();
+        }
+        b.mark(Cell::X, 2, 2).unwrap();
+
+        // Horizontal, vertical, diagonal and anti-diagonal lines through (3, 2)
+        assert_eq!(b.get_line_lengths(3, 2), [6, 2, 1, 1]);
+        assert_eq!(b.get_line_lengths(0, 0), [0; 4]);
+    }
+
+    #[test]
+    fn test_rectangular_symmetries() {
+        let mut b = Board::with_size(2, 3);
+        b.mark(Cell::X, 0, 0).unwrap();
+        assert_eq!(b.get_symmetries().len(), 4);
+
+        // Corners of a 3x2 board are all symmetric to each other
+        let (canonical, _) = b.get_canonical();
+        for (row_index, col_index) in [(0, 2), (1, 0), (1, 2)] {
+            let mut other = Board::with_size(2, 3);
+            other.mark(Cell::X, row_index, col_index).unwrap();
+            assert_eq!(other.get_canonical().0, canonical);
+        }
+
+        // A quarter turn swaps width and height
+        let rotated = b.get_rotated();
+        assert_eq!((rotated.get_width(), rotated.get_height()), (2, 3));
+        assert_eq!(rotated.get_cell(0, 1).unwrap(), Cell::X);
+    }
+
+    #[test]
+    fn test_display_board_with_size() {
+        let mut b = Board::with_size(2, 4);
+        b.mark(Cell::X, 0, 0).unwrap();
+        b.mark(Cell::O, 1, 3).unwrap();
+
+        assert_eq!(
+            b.to_string(),
+            " X |   |   |   \n---------------\n   |   |   | O "
+        );
+    }
 }
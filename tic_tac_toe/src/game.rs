@@ -1,12 +1,32 @@
 //! Contains functionality for constructing and playing a Tic-Tac-Toe game
+//!
+//! Besides classic Tic-Tac-Toe, games can be m,n,k-games: played on a board of any width and height, and won by
+//! the first player to get k marks in a row, horizontally, vertically or diagonally.
+//!
+//! Games can also follow other rules than the standard ones (see `GameRules`): misère, where completing a line
+//! loses, wild, where players may place either mark, and Notakto, where both players place X on one or more
+//! boards.
+//!
 //! ## Examples
 //!
 //! ```
-//! use tic_tac_toe::game;
+//! use tic_tac_toe::{board, game};
 //!
 //! let mut game = game::Game::new();
 //! game.play(0, 0); // Player X plays at position (0, 0)
 //! game.play(1, 1); // Player O plays at position (1, 1)
+//!
+//! // Gomoku-style 15x15 board with 5 in a row
+//! let mut game = game::Game::with_size(15, 15, 5).unwrap();
+//! game.play(7, 7).unwrap();
+//!
+//! // Wild Tic-Tac-Toe, where player X places an O
+//! let mut game = game::Game::with_rules(3, 3, 3, game::GameRules::Wild).unwrap();
+//! game.play_mark(board::Cell::O, 1, 1).unwrap();
+//!
+//! // Notakto on two 3x3 boards, laid side by side: columns 3 to 5 belong to the second board
+//! let mut game = game::Game::with_rules(3, 3, 3, game::GameRules::Notakto { boards: 2 }).unwrap();
+//! game.play(0, 4).unwrap();
 //! ```
 
 use crate::board;
@@ -28,33 +48,104 @@ pub enum GameState {
     Tie,
 }
 
+/// Represents the rules deciding how a game is won
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GameRules {
+    /// Each player places their own mark, and the first player to complete a line wins
+    #[default]
+    Standard,
+    /// Each player places their own mark, and the first player to complete a line loses
+    Misere,
+    /// Each player places either mark, and the first player to complete a line of either mark wins
+    Wild,
+    /// Both players place X on `boards` boards laid side by side. A board is dead once it holds a line, and no
+    /// mark can be placed on it anymore. The player who kills the last board loses
+    Notakto { boards: usize },
+}
+
 /// Represents the game objects. Interally, it keeps track of:
-/// - the current board state
+/// - the current board state. Under Notakto, all boards are kept side by side on a single board
+/// - the number of marks in a row needed to win
+/// - the rules of the game
+/// - which boards are dead, which only ever happens under Notakto
 /// - the turn of the current player
 /// - the state of the game (i.e, Ongoing, X won, O won, tie)
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Game {
     board: board::Board,
+    win_length: usize,
+    rules: GameRules,
+    dead_boards: Vec<bool>,
     turn: GameTurn,
     state: GameState,
 }
 
+/// Represents the possible reasons when failing to construct a game of a given size
+#[derive(Debug, PartialEq, Eq)]
+pub enum GameSizeError {
+    EmptyBoard,
+    ZeroWinLength,
+}
+
 /// Represents the possible reasons when failing to mark a board cell
 #[derive(Debug, PartialEq, Eq)]
 pub enum GamePlayError {
     MarkError(board::BoardMarkError),
+    ForbiddenMark,
+    DeadBoard,
     GameIsOver,
 }
 
 impl Game {
-    /// Initializes a new `Game` object
+    /// Initializes a new `Game` object, on a 3x3 board with 3 in a row to win
     pub fn new() -> Self {
-        Game {
-            board: board::Board::new(),
+        Game::with_size(3, 3, 3).unwrap()
+    }
+
+    /// Initializes a new `Game` object on a board with `height` rows and `width` columns, won by the first player
+    /// to get `win_length` marks in a row. Returns an `Err` if:
+    /// - the board has no cell, or
+    /// - `win_length` is 0
+    pub fn with_size(
+        height: usize,
+        width: usize,
+        win_length: usize,
+    ) -> Result<Self, GameSizeError> {
+        Game::with_rules(height, width, win_length, GameRules::Standard)
+    }
+
+    /// Initializes a new `Game` object following `rules`, on boards with `height` rows and `width` columns where
+    /// lines are made of `win_length` marks in a row. Returns an `Err` if:
+    /// - the board has no cell, or Notakto is played on no board, or
+    /// - `win_length` is 0
+    pub fn with_rules(
+        height: usize,
+        width: usize,
+        win_length: usize,
+        rules: GameRules,
+    ) -> Result<Self, GameSizeError> {
+        let board_count = match rules {
+            GameRules::Notakto { boards } => boards,
+            _ => 1,
+        };
+
+        if width == 0 || height == 0 || board_count == 0 {
+            return Err(GameSizeError::EmptyBoard);
+        }
+
+        if win_length == 0 {
+            return Err(GameSizeError::ZeroWinLength);
+        }
+
+        Ok(Game {
+            board: board::Board::with_size(height, board_count * width),
+            win_length,
+            rules,
+            dead_boards: vec![false; board_count],
             turn: GameTurn::TurnX,
             state: GameState::Ongoing,
-        }
+        })
     }
 
     /// Gets the current state of the game
@@ -63,6 +154,7 @@ impl Game {
     }
 
     /// Returns a boolean indicating whether the game is over
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_over(&self) -> bool {
         match self.state {
             GameState::Ongoing => false,
@@ -75,113 +167,289 @@ impl Game {
         self.turn
     }
 
-    fn check_win(&self, path: [(usize, usize); 3]) -> (u32, u32) {
-        let mut x_streak = 0;
-        let mut o_streak = 0;
+    /// Gets the board of the game. Under Notakto, all boards are laid side by side on it, from left to right
+    pub fn get_board(&self) -> &board::Board {
+        &self.board
+    }
 
-        for coord in path {
-            match self.board.get_cell(coord.0, coord.1).unwrap() {
-                board::Cell::X => x_streak += 1,
-                board::Cell::O => o_streak += 1,
-                _ => {}
-            }
+    /// Gets the number of marks in a row needed to win
+    pub fn get_win_length(&self) -> usize {
+        self.win_length
+    }
+
+    /// Gets the rules of the game
+    pub fn get_rules(&self) -> GameRules {
+        self.rules
+    }
+
+    /// Gets the number of boards the game is played on, which is only ever more than one under Notakto
+    pub fn get_board_count(&self) -> usize {
+        match self.rules {
+            GameRules::Notakto { boards } => boards,
+            _ => 1,
         }
+    }
 
-        (x_streak, o_streak)
+    /// Gets the number of columns of a single board
+    fn get_board_width(&self) -> usize {
+        self.board.get_width() / self.get_board_count()
     }
 
-    fn update_state(&mut self) {
-        if self.is_over() {
-            panic!("Cannot update state when game is terminated!")
+    /// Gets the mark placed by the current player when playing with `play`: X under Notakto, and the player's own
+    /// mark otherwise
+    pub fn get_mark(&self) -> board::Cell {
+        match (self.rules, self.turn) {
+            (GameRules::Notakto { .. }, _) | (_, GameTurn::TurnX) => board::Cell::X,
+            (_, GameTurn::TurnO) => board::Cell::O,
         }
+    }
 
-        let paths: [[(usize, usize); 3]; 8] = [
-            // Row win paths
-            [(0, 0), (0, 1), (0, 2)],
-            [(1, 0), (1, 1), (1, 2)],
-            [(2, 0), (2, 1), (2, 2)],
-            // Column win paths
-            [(0, 0), (1, 0), (2, 0)],
-            [(0, 1), (1, 1), (2, 1)],
-            [(0, 2), (1, 2), (2, 2)],
-            // Diagonal win paths
-            [(0, 0), (1, 1), (2, 2)],
-            [(0, 2), (1, 1), (2, 0)],
-        ];
-
-        let mut found_empty = false;
-        for path in paths {
-            match self.check_win(path) {
-                (3, _) => {
-                    self.state = GameState::XWon;
-                    return;
-                }
-                (_, 3) => {
-                    self.state = GameState::OWon;
-                    return;
+    /// Gets the marks the current player is allowed to place
+    pub fn get_allowed_marks(&self) -> Vec<board::Cell> {
+        match self.rules {
+            GameRules::Wild => vec![board::Cell::X, board::Cell::O],
+            _ => vec![self.get_mark()],
+        }
+    }
+
+    /// Returns a copy of the board with index `board_index`, counted from the left. Under other rules than
+    /// Notakto, this is the whole board
+    fn get_single_board(&self, board_index: usize) -> board::Board {
+        let board_width = self.get_board_width();
+        let mut single_board = board::Board::with_size(self.board.get_height(), board_width);
+
+        for row_index in 0..self.board.get_height() {
+            for col_index in 0..board_width {
+                let cell = self
+                    .board
+                    .get_cell(row_index, board_index * board_width + col_index)
+                    .unwrap();
+                if cell != board::Cell::Empty {
+                    single_board.mark(cell, row_index, col_index).unwrap();
                 }
-                (x_streak, o_streak) if x_streak + o_streak < 3 => found_empty = true,
-                _ => {}
             }
         }
 
-        if !found_empty {
-            self.state = GameState::Tie;
+        single_board
+    }
+
+    /// Gets the number of marks in the longest line going through the cell at location (`row_index`,
+    /// `col_index`), without crossing over to a neighbouring Notakto board
+    fn get_longest_line(&self, row_index: usize, col_index: usize) -> usize {
+        if self.get_board_count() == 1 {
+            return self.board.get_longest_line(row_index, col_index);
         }
+
+        let board_width = self.get_board_width();
+        self.get_single_board(col_index / board_width)
+            .get_longest_line(row_index, col_index % board_width)
+    }
+
+    /// Returns a boolean indicating whether every cell of the board with index `board_index` is marked
+    fn is_board_full(&self, board_index: usize) -> bool {
+        let board_width = self.get_board_width();
+
+        (0..self.board.get_height()).all(|row_index| {
+            (board_index * board_width..(board_index + 1) * board_width).all(|col_index| {
+                self.board.get_cell(row_index, col_index).unwrap() != board::Cell::Empty
+            })
+        })
+    }
+
+    /// Returns a boolean indicating whether the board with index `board_index` holds a line, or is full. Scans
+    /// the whole board, while `update_state` only looks at the last move
+    fn holds_line_or_is_full(&self, board_index: usize) -> bool {
+        let single_board = self.get_single_board(board_index);
+
+        single_board.is_full()
+            || (0..single_board.get_height()).any(|row_index| {
+                (0..single_board.get_width()).any(|col_index| {
+                    single_board.get_longest_line(row_index, col_index) >= self.win_length
+                })
+            })
     }
 
-    /// Plays one turn of Tic-Tac-Toe as the current player by marking cell at location (`row_index`, `col_index`).
-    /// Returns an `Err` if:
+    /// Updates the state of the game after the current player marked the cell at location
+    /// (`row_index`, `col_index`). Only lines going through that cell can have been completed
+    fn update_state(&mut self, row_index: usize, col_index: usize) {
+        if self.is_over() {
+            panic!("Cannot update state when game is terminated!")
+        }
+
+        let (player_won, opponent_won) = match self.turn {
+            GameTurn::TurnX => (GameState::XWon, GameState::OWon),
+            GameTurn::TurnO => (GameState::OWon, GameState::XWon),
+        };
+
+        let completed_line = self.get_longest_line(row_index, col_index) >= self.win_length;
+
+        self.state = match self.rules {
+            GameRules::Standard | GameRules::Wild if completed_line => player_won,
+            GameRules::Misere if completed_line => opponent_won,
+            GameRules::Notakto { .. } => {
+                let board_index = col_index / self.get_board_width();
+                self.dead_boards[board_index] = completed_line || self.is_board_full(board_index);
+
+                if self.dead_boards.contains(&false) {
+                    GameState::Ongoing
+                } else if completed_line {
+                    opponent_won
+                } else {
+                    GameState::Tie
+                }
+            }
+            _ if self.board.is_full() => GameState::Tie,
+            _ => GameState::Ongoing,
+        };
+    }
+
+    /// Plays one turn of Tic-Tac-Toe as the current player by marking cell at location (`row_index`, `col_index`)
+    /// with the mark given by `get_mark`. Returns an `Err` if:
     /// - location is out-of-bounds, or
     /// - cell played is non-empty, or
+    /// - cell played is on a dead Notakto board, or
     /// - game is terminated (not `Ongoing`)
     pub fn play(&mut self, row_index: usize, col_index: usize) -> Result<(), GamePlayError> {
-        match self.state {
-            GameState::Ongoing => match self.turn {
-                GameTurn::TurnX => {
-                    if let Err(e) = self.board.mark(board::Cell::X, row_index, col_index) {
-                        return Err(GamePlayError::MarkError(e));
-                    }
-
-                    self.update_state();
-                    self.turn = GameTurn::TurnO;
-                    Ok(())
-                }
-                GameTurn::TurnO => {
-                    if let Err(e) = self.board.mark(board::Cell::O, row_index, col_index) {
-                        return Err(GamePlayError::MarkError(e));
-                    }
-
-                    self.update_state();
-                    self.turn = GameTurn::TurnX;
-                    Ok(())
-                }
-            },
-            _ => Err(GamePlayError::GameIsOver),
+        self.play_mark(self.get_mark(), row_index, col_index)
+    }
+
+    /// Plays one turn of Tic-Tac-Toe as the current player by marking cell at location (`row_index`, `col_index`)
+    /// with `mark`. Returns an `Err` if:
+    /// - `mark` is not one of `get_allowed_marks`, or
+    /// - location is out-of-bounds, or
+    /// - cell played is non-empty, or
+    /// - cell played is on a dead Notakto board, or
+    /// - game is terminated (not `Ongoing`)
+    pub fn play_mark(
+        &mut self,
+        mark: board::Cell,
+        row_index: usize,
+        col_index: usize,
+    ) -> Result<(), GamePlayError> {
+        if self.is_over() {
+            return Err(GamePlayError::GameIsOver);
+        }
+
+        if !self.get_allowed_marks().contains(&mark) {
+            return Err(GamePlayError::ForbiddenMark);
+        }
+
+        if col_index < self.board.get_width()
+            && self.dead_boards[col_index / self.get_board_width()]
+        {
+            return Err(GamePlayError::DeadBoard);
+        }
+
+        if let Err(e) = self.board.mark(mark, row_index, col_index) {
+            return Err(GamePlayError::MarkError(e));
         }
+
+        self.update_state(row_index, col_index);
+        self.turn = match self.turn {
+            GameTurn::TurnX => GameTurn::TurnO,
+            GameTurn::TurnO => GameTurn::TurnX,
+        };
+        Ok(())
     }
 
     /// Returns a copy of the game state after the move (row_index, col_index) has been played
     pub fn get_played(&self, row_index: usize, col_index: usize) -> Result<Self, GamePlayError> {
+        self.get_played_mark(self.get_mark(), row_index, col_index)
+    }
+
+    /// Returns a copy of the game state after `mark` has been placed at (row_index, col_index)
+    pub fn get_played_mark(
+        &self,
+        mark: board::Cell,
+        row_index: usize,
+        col_index: usize,
+    ) -> Result<Self, GamePlayError> {
         let mut cloned_game = (*self).clone();
-        cloned_game.play(row_index, col_index)?;
+        cloned_game.play_mark(mark, row_index, col_index)?;
         Ok(cloned_game)
     }
 
-    // Returns a vector of possible move as (row_index, col_index).
+    // Returns a vector of possible move as (row_index, col_index), skipping cells of dead Notakto boards.
     // List of moves is always ordered upper left -> bottom right
     pub fn get_possible_plays(&self) -> Vec<(usize, usize)> {
         if self.is_over() {
             return Vec::new();
         }
 
-        (0..=2)
-            .flat_map(|row_index| (0..=2).map(move |col_index| (row_index, col_index)))
+        let board_width = self.get_board_width();
+
+        (0..self.board.get_height())
+            .flat_map(|row_index| {
+                (0..self.board.get_width()).map(move |col_index| (row_index, col_index))
+            })
             .filter(|&(row_index, col_index)| {
-                self.board.get_cell(row_index, col_index).unwrap() == board::Cell::Empty
+                !self.dead_boards[col_index / board_width]
+                    && self.board.get_cell(row_index, col_index).unwrap() == board::Cell::Empty
             })
             .collect()
     }
+
+    // Returns a vector of possible move as (row_index, col_index, mark), with every allowed mark for each cell.
+    // List of moves is ordered like `get_possible_plays`, then by mark
+    pub fn get_possible_marked_plays(&self) -> Vec<(usize, usize, board::Cell)> {
+        let allowed_marks = self.get_allowed_marks();
+
+        self.get_possible_plays()
+            .into_iter()
+            .flat_map(|(row_index, col_index)| {
+                allowed_marks
+                    .iter()
+                    .map(move |&mark| (row_index, col_index, mark))
+            })
+            .collect()
+    }
+
+    /// Returns a copy of the game with the board moved by `symmetry`. Turn and state are left unchanged, since
+    /// symmetries preserve winning lines. Under Notakto, boards are interchangeable, so symmetries that keep the
+    /// shape of all boards side by side also move each board onto a symmetric one
+    pub fn get_transformed(&self, symmetry: board::Symmetry) -> Self {
+        let mut transformed = Game {
+            board: self.board.get_transformed(symmetry),
+            win_length: self.win_length,
+            rules: self.rules,
+            dead_boards: self.dead_boards.clone(),
+            turn: self.turn,
+            state: self.state,
+        };
+
+        // Symmetries reversing the order of the boards also reverse which of them are dead
+        if let GameRules::Notakto { boards } = self.rules {
+            transformed.dead_boards = (0..boards)
+                .map(|board_index| transformed.holds_line_or_is_full(board_index))
+                .collect();
+        }
+
+        transformed
+    }
+
+    /// Returns the canonical form of the game, in which the board is in canonical form, together with the
+    /// symmetry that maps the game onto it. Boards of a Notakto game are only moved by symmetries keeping their
+    /// shape, as others would mix them up
+    pub fn get_canonical(&self) -> (Self, board::Symmetry) {
+        let symmetry = if self.get_board_count() == 1 {
+            self.board.get_canonical().1
+        } else {
+            self.board
+                .get_symmetries()
+                .into_iter()
+                .filter(|symmetry| symmetry.preserves_shape())
+                .min_by_key(|&symmetry| self.board.get_transformed(symmetry))
+                .unwrap()
+        };
+
+        (self.get_transformed(symmetry), symmetry)
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
 }
 
 impl fmt::Display for Game {
@@ -199,7 +467,31 @@ impl fmt::Display for Game {
         };
 
         writeln!(f, "Game State: {}, Player Turn: {}", game_state, game_turn)?;
-        writeln!(f, "\n\n{}", self.board)?;
+
+        if self.get_board_count() == 1 {
+            writeln!(f, "\n\n{}", self.board)?;
+            return Ok(());
+        }
+
+        // Notakto boards are shown side by side, separated by a vertical bar
+        writeln!(f)?;
+        let board_width = self.get_board_width();
+        for row_index in 0..self.board.get_height() {
+            for col_index in 0..self.board.get_width() {
+                if col_index > 0 && col_index % board_width == 0 {
+                    write!(f, " |")?;
+                }
+
+                let symbol = match self.board.get_cell(row_index, col_index).unwrap() {
+                    board::Cell::X => "X",
+                    board::Cell::O => "O",
+                    board::Cell::Empty => ".",
+                };
+                write!(f, " {}", symbol)?;
+            }
+
+            writeln!(f)?;
+        }
         Ok(())
     }
 }
@@ -417,4 +709,235 @@ mod tests {
         assert_eq!(game.state, GameState::Ongoing);
         assert_eq!(game_clone.state, GameState::XWon);
     }
+
+    #[test]
+    fn test_canonical_game() {
+        let mut first = Game::new();
+        first.play(0, 0).unwrap();
+        first.play(1, 2).unwrap();
+
+        // The same position mirrored across the anti-diagonal
+        let mut second = Game::new();
+        second.play(2, 2).unwrap();
+        second.play(0, 1).unwrap();
+
+        let (first_canonical, _) = first.get_canonical();
+        let (second_canonical, symmetry) = second.get_canonical();
+        assert!(first_canonical == second_canonical);
+        assert_eq!(second_canonical.turn, GameTurn::TurnX);
+
+        // Moves map back from the canonical form to the real board through the inverse symmetry
+        let (row_index, col_index) = symmetry.map_location(2, 2, 3, 3);
+        assert_eq!(
+            symmetry.inverse().map_location(row_index, col_index, 3, 3),
+            (2, 2)
+        );
+    }
+
+    #[test]
+    fn test_with_size_errors() {
+        assert_eq!(
+            Game::with_size(3, 0, 3).err(),
+            Some(GameSizeError::EmptyBoard)
+        );
+        assert_eq!(
+            Game::with_size(3, 3, 0).err(),
+            Some(GameSizeError::ZeroWinLength)
+        );
+    }
+
+    #[test]
+    fn test_x_won_three_in_a_row_on_4x4() {
+        let mut game = Game::with_size(4, 4, 3).unwrap();
+        for (row_index, col_index) in [(1, 1), (0, 0), (2, 2), (0, 3)] {
+            game.play(row_index, col_index).unwrap();
+        }
+        assert_eq!(game.state, GameState::Ongoing);
+
+        // X at (3, 3) completes the diagonal (1, 1), (2, 2), (3, 3)
+        game.play(3, 3).unwrap();
+        assert_eq!(game.state, GameState::XWon);
+    }
+
+    #[test]
+    fn test_o_won_five_in_a_row_on_15x15() {
+        let mut game = Game::with_size(15, 15, 5).unwrap();
+        for col_index in 10..14 {
+            game.play(0, col_index - 10).unwrap();
+            game.play(14, col_index).unwrap();
+        }
+        // X at (7, 7) leaves O with four in a row
+        game.play(7, 7).unwrap();
+        assert_eq!(game.state, GameState::Ongoing);
+
+        game.play(14, 9).unwrap();
+        assert_eq!(game.state, GameState::OWon);
+    }
+
+    #[test]
+    fn test_rectangular_anti_diagonal_win_and_plays() {
+        // 4 rows, 5 columns, 4 in a row
+        let mut game = Game::with_size(4, 5, 4).unwrap();
+        assert_eq!(game.get_possible_plays().len(), 20);
+        assert_eq!(game.get_possible_plays()[19], (3, 4));
+
+        for (row_index, col_index) in [(0, 4), (0, 0), (1, 3), (1, 0), (2, 2), (2, 0)] {
+            game.play(row_index, col_index).unwrap();
+        }
+        assert_eq!(game.state, GameState::Ongoing);
+
+        game.play(3, 1).unwrap();
+        assert_eq!(game.state, GameState::XWon);
+        assert!(game.get_possible_plays().is_empty());
+    }
+
+    #[test]
+    fn test_tie_without_winning_line() {
+        // Nobody can get 3 in a row on a 2x2 board
+        let mut game = Game::with_size(2, 2, 3).unwrap();
+        for (row_index, col_index) in [(0, 0), (0, 1), (1, 1), (1, 0)] {
+            game.play(row_index, col_index).unwrap();
+        }
+        assert_eq!(game.state, GameState::Tie);
+    }
+
+    #[test]
+    fn test_misere_completing_line_loses() {
+        let mut game = Game::with_rules(3, 3, 3, GameRules::Misere).unwrap();
+        for (row_index, col_index) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            game.play(row_index, col_index).unwrap();
+        }
+        assert_eq!(game.state, GameState::Ongoing);
+
+        // X at (0, 2) completes the first row -> O won
+        game.play(0, 2).unwrap();
+        assert_eq!(game.state, GameState::OWon);
+    }
+
+    #[test]
+    fn test_misere_tie() {
+        // Same full board as in `test_tie`, which has no line
+        let mut game = Game::with_rules(3, 3, 3, GameRules::Misere).unwrap();
+        for (row_index, col_index) in [
+            (2, 0),
+            (1, 1),
+            (2, 2),
+            (2, 1),
+            (1, 2),
+            (1, 0),
+            (0, 1),
+            (0, 2),
+            (0, 0),
+        ] {
+            game.play(row_index, col_index).unwrap();
+        }
+        assert_eq!(game.state, GameState::Tie);
+    }
+
+    #[test]
+    fn test_wild_line_of_either_mark_wins() {
+        let mut game = Game::with_rules(3, 3, 3, GameRules::Wild).unwrap();
+        assert_eq!(game.get_possible_marked_plays().len(), 18);
+
+        // X places O at (0, 0), O places O at (0, 1)
+        game.play_mark(board::Cell::O, 0, 0).unwrap();
+        game.play_mark(board::Cell::O, 0, 1).unwrap();
+        assert_eq!(game.state, GameState::Ongoing);
+
+        // X places O at (0, 2), completing a row of O -> X won
+        game.play_mark(board::Cell::O, 0, 2).unwrap();
+        assert_eq!(game.state, GameState::XWon);
+    }
+
+    #[test]
+    fn test_forbidden_mark() {
+        let mut game = Game::new();
+        assert_eq!(
+            game.play_mark(board::Cell::O, 0, 0),
+            Err(GamePlayError::ForbiddenMark)
+        );
+        assert_eq!(game.get_possible_marked_plays()[0], (0, 0, board::Cell::X));
+
+        let mut game = Game::with_rules(3, 3, 3, GameRules::Wild).unwrap();
+        assert_eq!(
+            game.play_mark(board::Cell::Empty, 0, 0),
+            Err(GamePlayError::ForbiddenMark)
+        );
+
+        // Both players place X under Notakto
+        let mut game = Game::with_rules(3, 3, 3, GameRules::Notakto { boards: 1 }).unwrap();
+        game.play(0, 0).unwrap();
+        assert_eq!(game.get_mark(), board::Cell::X);
+        assert_eq!(
+            game.play_mark(board::Cell::O, 1, 1),
+            Err(GamePlayError::ForbiddenMark)
+        );
+        assert_eq!(game.turn, GameTurn::TurnO);
+    }
+
+    #[test]
+    fn test_notakto_completing_line_loses() {
+        let mut game = Game::with_rules(3, 3, 3, GameRules::Notakto { boards: 1 }).unwrap();
+        for (row_index, col_index) in [(0, 0), (0, 1)] {
+            game.play(row_index, col_index).unwrap();
+        }
+        assert_eq!(game.state, GameState::Ongoing);
+
+        // X places the third X of the first row -> O won
+        game.play(0, 2).unwrap();
+        assert_eq!(game.state, GameState::OWon);
+    }
+
+    #[test]
+    fn test_notakto_dead_boards() {
+        let mut game = Game::with_rules(3, 3, 3, GameRules::Notakto { boards: 2 }).unwrap();
+        assert_eq!(game.get_possible_plays().len(), 18);
+
+        // Lines do not run across boards: (0, 1), (0, 2) and (0, 3) are not a line of either board
+        for (row_index, col_index) in [(0, 1), (0, 2), (0, 3)] {
+            game.play(row_index, col_index).unwrap();
+        }
+        assert_eq!(game.state, GameState::Ongoing);
+
+        // O kills the first board by completing its first row
+        game.play(0, 0).unwrap();
+        assert_eq!(game.state, GameState::Ongoing);
+        assert_eq!(game.get_possible_plays().len(), 8);
+        assert!(game
+            .get_possible_plays()
+            .iter()
+            .all(|&(_, col_index)| col_index >= 3));
+        assert_eq!(game.play(1, 1), Err(GamePlayError::DeadBoard));
+
+        // O kills the second board, which is the last one -> X won
+        game.play(0, 4).unwrap();
+        game.play(0, 5).unwrap();
+        assert_eq!(game.state, GameState::XWon);
+    }
+
+    #[test]
+    fn test_notakto_canonical_swaps_boards() {
+        let rules = GameRules::Notakto { boards: 2 };
+
+        let mut first = Game::with_rules(3, 3, 3, rules).unwrap();
+        first.play(0, 0).unwrap();
+
+        // The same position with the boards swapped and mirrored
+        let mut second = Game::with_rules(3, 3, 3, rules).unwrap();
+        second.play(0, 5).unwrap();
+
+        assert!(first.get_canonical().0 == second.get_canonical().0);
+    }
+
+    #[test]
+    fn test_with_rules_errors() {
+        assert_eq!(
+            Game::with_rules(3, 3, 3, GameRules::Notakto { boards: 0 }).err(),
+            Some(GameSizeError::EmptyBoard)
+        );
+        assert_eq!(
+            Game::with_rules(3, 3, 0, GameRules::Misere).err(),
+            Some(GameSizeError::ZeroWinLength)
+        );
+    }
 }
@@ -0,0 +1,949 @@
+//! Contains functionality for constructing and playing a Gomoku (five in a row) game
+//!
+//! Players take turns placing stones on a 15x15 board, X (black) first, and the first player to line up five
+//! stones horizontally, vertically or diagonally wins. The rules are selected when constructing the game:
+//! - whether exactly five stones in a row win, or five or more
+//! - whether renju forbidden moves apply to X: overlines of six or more, double-fours and double-threes
+//! - the opening protocol: free, pro or swap2
+//!
+//! Under swap2, the players choose their colors during the opening, so that the first player to act is not
+//! necessarily the one playing X. Players are therefore told apart by seat (`Player::First` and `Player::Second`),
+//! and stones by color (`GameTurn::TurnX` and `GameTurn::TurnO`).
+//!
+//! ## Examples
+//!
+//! ```
+//! use tic_tac_toe::gomoku;
+//!
+//! let rules = gomoku::GameRules {
+//!     win_rule: gomoku::WinRule::ExactlyFive,
+//!     renju: true,
+//!     opening: gomoku::Opening::Pro,
+//! };
+//! let mut game = gomoku::Game::with_rules(rules);
+//! game.play(gomoku::Move::Place(7, 7)).unwrap(); // X opens at the centre, as required by the pro opening
+//! game.play(gomoku::Move::Place(7, 8)).unwrap();
+//! ```
+
+use crate::board;
+use crate::game::GameTurn;
+use std::fmt;
+
+/// Number of rows and columns of the board
+pub const BOARD_SIZE: usize = 15;
+
+/// Number of stones in a row needed to win
+pub const WIN_LENGTH: usize = 5;
+
+/// Row and column index of the centre of the board
+const CENTER: usize = BOARD_SIZE / 2;
+
+/// Minimum distance from the centre, counted in rows or columns, of the second X stone under the pro opening
+const PRO_DISTANCE: usize = 3;
+
+/// Number of cells on each side of a stone looked at when classifying the lines going through it for renju
+const REACH: usize = WIN_LENGTH;
+
+/// Cells of a line centred on a stone, from `REACH` cells before it to `REACH` cells after it. Cells off the board
+/// are None
+type Line = [Option<board::Cell>; 2 * REACH + 1];
+
+/// Represents the number of stones in a row that wins the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WinRule {
+    /// Exactly five stones in a row win, while six or more (an overline) do not
+    ExactlyFive,
+    /// Five or more stones in a row win
+    #[default]
+    FiveOrMore,
+}
+
+/// Represents the protocol deciding the first stones of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Opening {
+    /// Players alternate from the first stone, the first player playing X
+    #[default]
+    Free,
+    /// Players alternate, the first player playing X, but the first stone must be at the centre of the board and
+    /// the second X stone at least 3 rows or columns away from the centre
+    Pro,
+    /// The first player places two X stones and one O stone. The second player then either takes X, takes O, or
+    /// places one more O stone and one more X stone and lets the first player take a color
+    Swap2,
+}
+
+/// Represents the rules of the game. Includes
+/// - number of stones in a row that wins the game
+/// - flag forbidding overlines, double-fours and double-threes to X, as in renju
+/// - protocol deciding the first stones of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GameRules {
+    pub win_rule: WinRule,
+    pub renju: bool,
+    pub opening: Opening,
+}
+
+/// Represents one of the two players, by the order in which they act
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Player {
+    First,
+    Second,
+}
+
+impl Player {
+    /// Returns the other player
+    pub fn other(self) -> Player {
+        match self {
+            Player::First => Player::Second,
+            Player::Second => Player::First,
+        }
+    }
+}
+
+/// Represents the state of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameState {
+    Ongoing,
+    FirstWon,
+    SecondWon,
+    Tie,
+}
+
+/// Represents a move: either placing a stone of the color to play at location (`row_index`, `col_index`), or
+/// taking a color during the swap2 opening
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Move {
+    Place(usize, usize),
+    TakeColor(GameTurn),
+}
+
+/// Represents the stage of the opening the game is at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Phase {
+    /// Players place stones of alternating colors, each playing their own color
+    Play,
+    /// The first player places the 3 opening stones of swap2
+    Swap2Opening,
+    /// The second player takes a color or places the 2 extra stones of swap2
+    Swap2FirstChoice,
+    /// The second player places the second extra stone of swap2
+    Swap2Extra,
+    /// The first player takes a color after the extra stones of swap2
+    Swap2SecondChoice,
+}
+
+/// Represents the game objects. Interally, it keeps track of:
+/// - the current board state and the number of stones on it
+/// - the rules of the game
+/// - the stage of the opening and the player playing X, once decided
+/// - the state of the game (i.e, Ongoing, first player won, second player won, tie)
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Game {
+    board: board::Board,
+    stones: usize,
+    rules: GameRules,
+    phase: Phase,
+    x_player: Option<Player>,
+    state: GameState,
+}
+
+/// Represents the possible reasons when failing to play a move
+#[derive(Debug, PartialEq, Eq)]
+pub enum GamePlayError {
+    MarkError(board::BoardMarkError),
+    ForbiddenMove,
+    OpeningRule,
+    ColorChoiceExpected,
+    PlacementExpected,
+    GameIsOver,
+}
+
+impl Game {
+    /// Initializes a new `Game` object with the default rules: five or more in a row, no forbidden moves and a
+    /// free opening
+    pub fn new() -> Self {
+        Game::with_rules(GameRules::default())
+    }
+
+    /// Initializes a new `Game` object following `rules`
+    pub fn with_rules(rules: GameRules) -> Self {
+        let (phase, x_player) = match rules.opening {
+            Opening::Free | Opening::Pro => (Phase::Play, Some(Player::First)),
+            Opening::Swap2 => (Phase::Swap2Opening, None),
+        };
+
+        Game {
+            board: board::Board::with_size(BOARD_SIZE, BOARD_SIZE),
+            stones: 0,
+            rules,
+            phase,
+            x_player,
+            state: GameState::Ongoing,
+        }
+    }
+
+    /// Gets the current state of the game
+    pub fn get_state(&self) -> GameState {
+        self.state
+    }
+
+    /// Returns a boolean indicating whether the game is over
+    pub fn is_over(&self) -> bool {
+        !matches!(self.state, GameState::Ongoing)
+    }
+
+    /// Gets the board of the game
+    pub fn get_board(&self) -> &board::Board {
+        &self.board
+    }
+
+    /// Gets the rules of the game
+    pub fn get_rules(&self) -> GameRules {
+        self.rules
+    }
+
+    /// Gets the player playing X, or `None` while colors are still to be chosen in the swap2 opening
+    pub fn get_x_player(&self) -> Option<Player> {
+        self.x_player
+    }
+
+    /// Gets the color of the next stone to be placed. Colors alternate from X, in the opening as well
+    pub fn get_color(&self) -> GameTurn {
+        if self.stones.is_multiple_of(2) {
+            GameTurn::TurnX
+        } else {
+            GameTurn::TurnO
+        }
+    }
+
+    /// Gets the player whose turn it is to act, either by placing a stone or by taking a color
+    pub fn get_turn(&self) -> Player {
+        match self.phase {
+            Phase::Swap2Opening | Phase::Swap2SecondChoice => Player::First,
+            Phase::Swap2FirstChoice | Phase::Swap2Extra => Player::Second,
+            Phase::Play => self.get_player(self.get_color()),
+        }
+    }
+
+    /// Returns a boolean indicating whether the current player must take a color rather than place a stone
+    pub fn is_choosing_color(&self) -> bool {
+        self.phase == Phase::Swap2SecondChoice
+    }
+
+    /// Returns the player placing stones of `color`. Colors that are not decided yet belong to the first player
+    /// for X and to the second player for O
+    fn get_player(&self, color: GameTurn) -> Player {
+        let x_player = self.x_player.unwrap_or(Player::First);
+        match color {
+            GameTurn::TurnX => x_player,
+            GameTurn::TurnO => x_player.other(),
+        }
+    }
+
+    /// Returns a boolean indicating whether `line` of stones has a run of X stones of exactly `WIN_LENGTH` through
+    /// its middle cell
+    fn is_five(line: &Line) -> bool {
+        Game::get_run_length(line) == WIN_LENGTH
+    }
+
+    /// Returns the number of X stones in the unbroken run going through the middle cell of `line`
+    fn get_run_length(line: &Line) -> usize {
+        let is_x = |cell: &&Option<board::Cell>| **cell == Some(board::Cell::X);
+        let before = line[..REACH].iter().rev().take_while(is_x).count();
+        let after = line[REACH + 1..].iter().take_while(is_x).count();
+
+        if line[REACH] == Some(board::Cell::X) {
+            1 + before + after
+        } else {
+            0
+        }
+    }
+
+    /// Returns the indices of the empty cells of `line` on which an X stone would make exactly five in a row
+    /// through the middle cell. Only cells less than `WIN_LENGTH` away from the middle cell can do so
+    fn get_five_points(line: &Line) -> impl Iterator<Item = usize> + '_ {
+        (REACH + 1 - WIN_LENGTH..REACH + WIN_LENGTH)
+            .filter(|&index| line[index] == Some(board::Cell::Empty))
+            .filter(|&index| {
+                let mut extended = *line;
+                extended[index] = Some(board::Cell::X);
+                Game::is_five(&extended)
+            })
+    }
+
+    /// Returns the number of cells of `line` on which an X stone would make exactly five in a row through the
+    /// middle cell, together with the distance between the first and last of them
+    fn get_five_point_spread(line: &Line) -> (usize, usize) {
+        let mut five_points = Game::get_five_points(line);
+        match five_points.next() {
+            Some(first) => five_points.fold((1, 0), |(count, _), index| (count + 1, index - first)),
+            None => (0, 0),
+        }
+    }
+
+    /// Returns a boolean indicating whether `line` has a straight four of X through its middle cell, i.e. four in
+    /// a row that can be made five on either end
+    fn is_straight_four(line: &Line) -> bool {
+        Game::get_five_point_spread(line) == (2, WIN_LENGTH)
+    }
+
+    /// Returns the number of fours of X through the middle cell of `line`, i.e. of ways to make five with one more
+    /// stone. A straight four counts once even though it can be completed on either end
+    fn count_fours(line: &Line) -> usize {
+        match Game::get_five_point_spread(line) {
+            (2, WIN_LENGTH) => 1,
+            (count, _) => count,
+        }
+    }
+
+    /// Returns a boolean indicating whether `line` has an open three of X through its middle cell, i.e. whether
+    /// one more stone makes a straight four
+    fn is_open_three(line: &Line) -> bool {
+        (REACH + 2 - WIN_LENGTH..REACH + WIN_LENGTH - 1)
+            .filter(|&index| line[index] == Some(board::Cell::Empty))
+            .any(|index| {
+                let mut extended = *line;
+                extended[index] = Some(board::Cell::X);
+                Game::is_straight_four(&extended)
+            })
+    }
+
+    /// Returns a boolean indicating whether an X stone at empty location (`row_index`, `col_index`) is forbidden
+    /// by renju rules: a move making exactly five always wins, and is otherwise forbidden if it makes an
+    /// overline, two fours or two open threes. Threes are not checked for being completable by a move that is
+    /// itself forbidden
+    fn is_forbidden(&self, row_index: usize, col_index: usize) -> bool {
+        let lines: [Line; 4] = board::LINE_DIRECTIONS.map(|(row_step, col_step)| {
+            let mut line = [None; 2 * REACH + 1];
+            for (index, cell) in line.iter_mut().enumerate() {
+                let offset = index as isize - REACH as isize;
+                let row = row_index as isize + offset * row_step;
+                let col = col_index as isize + offset * col_step;
+                if row >= 0 && col >= 0 {
+                    *cell = self.board.get_cell(row as usize, col as usize).ok();
+                }
+            }
+
+            line[REACH] = Some(board::Cell::X);
+            line
+        });
+
+        // Two fours or two threes in different lines need two lines with at least 2 other X stones, while two
+        // fours in the same line, as in X.XXX.X, need a single line with at least 4 other X stones, and an
+        // overline at least 5 of them
+        let x_counts = lines.map(|line| {
+            line.iter()
+                .filter(|&&cell| cell == Some(board::Cell::X))
+                .count()
+        });
+        if x_counts.iter().filter(|&&x_count| x_count > 2).count() < 2
+            && x_counts.iter().all(|&x_count| x_count < WIN_LENGTH)
+        {
+            return false;
+        }
+
+        if lines.iter().any(Game::is_five) {
+            return false;
+        }
+
+        if lines
+            .iter()
+            .any(|line| Game::get_run_length(line) > WIN_LENGTH)
+        {
+            return true;
+        }
+
+        // A four needs 3 other X stones less than `WIN_LENGTH` cells away, and an open three needs 2 of them less
+        // than `WIN_LENGTH - 1` cells away
+        let count_near_x = |line: &Line, distance: usize| {
+            line[REACH - distance..=REACH + distance]
+                .iter()
+                .filter(|&&cell| cell == Some(board::Cell::X))
+                .count()
+                - 1
+        };
+
+        let fours = lines.map(|line| {
+            if count_near_x(&line, WIN_LENGTH - 1) >= WIN_LENGTH - 2 {
+                Game::count_fours(&line)
+            } else {
+                0
+            }
+        });
+        if fours.iter().sum::<usize>() >= 2 {
+            return true;
+        }
+
+        lines
+            .iter()
+            .zip(fours)
+            .filter(|&(line, four_count)| {
+                four_count == 0
+                    && count_near_x(line, WIN_LENGTH - 2) >= 2
+                    && Game::is_open_three(line)
+            })
+            .count()
+            >= 2
+    }
+
+    /// Returns a boolean indicating whether a stone of the color to play can be placed at location
+    /// (`row_index`, `col_index`) under the opening protocol and the renju rules. Assumes location is empty
+    fn is_allowed(&self, row_index: usize, col_index: usize) -> bool {
+        if self.rules.opening == Opening::Pro {
+            let distance = row_index.abs_diff(CENTER).max(col_index.abs_diff(CENTER));
+            match self.stones {
+                0 if distance != 0 => return false,
+                2 if distance < PRO_DISTANCE => return false,
+                _ => {}
+            }
+        }
+
+        !(self.rules.renju
+            && self.get_color() == GameTurn::TurnX
+            && self.is_forbidden(row_index, col_index))
+    }
+
+    /// Returns a boolean indicating whether the current player can place a stone anywhere
+    fn has_allowed_placement(&self) -> bool {
+        (0..BOARD_SIZE)
+            .flat_map(|row_index| (0..BOARD_SIZE).map(move |col_index| (row_index, col_index)))
+            .any(|(row_index, col_index)| {
+                self.board.get_cell(row_index, col_index).unwrap() == board::Cell::Empty
+                    && self.is_allowed(row_index, col_index)
+            })
+    }
+
+    /// Updates the state of the game after a stone of `color` was placed at location (`row_index`, `col_index`).
+    /// Only lines going through that stone can have been completed
+    fn update_state(&mut self, color: GameTurn, row_index: usize, col_index: usize) {
+        if self.is_over() {
+            panic!("Cannot update state when game is terminated!")
+        }
+
+        let line_lengths = self.board.get_line_lengths(row_index, col_index);
+        let is_won = match self.rules.win_rule {
+            WinRule::ExactlyFive => line_lengths.contains(&WIN_LENGTH),
+            WinRule::FiveOrMore => line_lengths.iter().any(|&length| length >= WIN_LENGTH),
+        };
+
+        if is_won {
+            self.state = match self.get_player(color) {
+                Player::First => GameState::FirstWon,
+                Player::Second => GameState::SecondWon,
+            };
+        } else if self.board.is_full()
+            || (self.phase == Phase::Play && !self.has_allowed_placement())
+        {
+            self.state = GameState::Tie;
+        }
+    }
+
+    /// Plays `game_move` as the current player. Returns an `Err` if:
+    /// - a stone is placed out-of-bounds, or on a non-empty cell, or
+    /// - a stone is placed on a cell forbidden to X by renju rules, or
+    /// - a stone is placed on a cell not allowed by the pro opening, or
+    /// - a stone is placed when a color must be taken, or the other way round, or
+    /// - game is terminated (not `Ongoing`)
+    pub fn play(&mut self, game_move: Move) -> Result<(), GamePlayError> {
+        if self.is_over() {
+            return Err(GamePlayError::GameIsOver);
+        }
+
+        match game_move {
+            Move::TakeColor(color) => {
+                if !matches!(
+                    self.phase,
+                    Phase::Swap2FirstChoice | Phase::Swap2SecondChoice
+                ) {
+                    return Err(GamePlayError::PlacementExpected);
+                }
+
+                let player = self.get_turn();
+                self.x_player = Some(match color {
+                    GameTurn::TurnX => player,
+                    GameTurn::TurnO => player.other(),
+                });
+                self.phase = Phase::Play;
+                Ok(())
+            }
+            Move::Place(row_index, col_index) => {
+                if self.phase == Phase::Swap2SecondChoice {
+                    return Err(GamePlayError::ColorChoiceExpected);
+                }
+
+                match self.board.get_cell(row_index, col_index) {
+                    Err(_) => {
+                        return Err(GamePlayError::MarkError(board::BoardMarkError::OutOfBound))
+                    }
+                    Ok(board::Cell::X | board::Cell::O) => {
+                        return Err(GamePlayError::MarkError(
+                            board::BoardMarkError::NonEmptyCell,
+                        ))
+                    }
+                    Ok(board::Cell::Empty) => {}
+                }
+
+                if !self.is_allowed(row_index, col_index) {
+                    return Err(match self.rules.opening {
+                        Opening::Pro if self.stones < 3 => GamePlayError::OpeningRule,
+                        _ => GamePlayError::ForbiddenMove,
+                    });
+                }
+
+                let color = self.get_color();
+                let mark = match color {
+                    GameTurn::TurnX => board::Cell::X,
+                    GameTurn::TurnO => board::Cell::O,
+                };
+                self.board.mark(mark, row_index, col_index).unwrap();
+                self.stones += 1;
+
+                self.phase = match (self.phase, self.stones) {
+                    (Phase::Swap2Opening, 3) => Phase::Swap2FirstChoice,
+                    (Phase::Swap2FirstChoice, _) => Phase::Swap2Extra,
+                    (Phase::Swap2Extra, _) => Phase::Swap2SecondChoice,
+                    (phase, _) => phase,
+                };
+
+                self.update_state(color, row_index, col_index);
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns a copy of the game state after `game_move` has been played
+    pub fn get_played(&self, game_move: Move) -> Result<Self, GamePlayError> {
+        let mut cloned_game = (*self).clone();
+        cloned_game.play(game_move)?;
+        Ok(cloned_game)
+    }
+
+    // Returns a vector of possible moves. Color choices come first, then stones ordered upper left -> bottom right
+    pub fn get_possible_plays(&self) -> Vec<Move> {
+        if self.is_over() {
+            return Vec::new();
+        }
+
+        let mut possible_plays = Vec::new();
+        if matches!(
+            self.phase,
+            Phase::Swap2FirstChoice | Phase::Swap2SecondChoice
+        ) {
+            possible_plays.push(Move::TakeColor(GameTurn::TurnX));
+            possible_plays.push(Move::TakeColor(GameTurn::TurnO));
+        }
+
+        if self.phase != Phase::Swap2SecondChoice {
+            possible_plays.extend(
+                (0..BOARD_SIZE)
+                    .flat_map(|row_index| {
+                        (0..BOARD_SIZE).map(move |col_index| (row_index, col_index))
+                    })
+                    .filter(|&(row_index, col_index)| {
+                        self.board.get_cell(row_index, col_index).unwrap() == board::Cell::Empty
+                            && self.is_allowed(row_index, col_index)
+                    })
+                    .map(|(row_index, col_index)| Move::Place(row_index, col_index)),
+            );
+        }
+
+        possible_plays
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let game_state = match self.state {
+            GameState::Ongoing => "Ongoing",
+            GameState::FirstWon => "First Player Won",
+            GameState::SecondWon => "Second Player Won",
+            GameState::Tie => "Tie",
+        };
+
+        let player_turn = match self.get_turn() {
+            Player::First => "First",
+            Player::Second => "Second",
+        };
+
+        let action = if self.is_choosing_color() {
+            "Take Color"
+        } else {
+            match self.get_color() {
+                GameTurn::TurnX => "Place X",
+                GameTurn::TurnO => "Place O",
+            }
+        };
+
+        writeln!(
+            f,
+            "Game State: {}, Player Turn: {} ({})",
+            game_state, player_turn, action
+        )?;
+        writeln!(f, "\n\n{}", self.board)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Places stones at `locations` from an empty board, following `rules`
+    fn game_after(rules: GameRules, locations: &[(usize, usize)]) -> Game {
+        let mut game = Game::with_rules(rules);
+        for &(row_index, col_index) in locations {
+            game.play(Move::Place(row_index, col_index)).unwrap();
+        }
+        game
+    }
+
+    fn renju_rules() -> GameRules {
+        GameRules {
+            win_rule: WinRule::ExactlyFive,
+            renju: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_initialize_game() {
+        let game = Game::new();
+        assert_eq!(game.state, GameState::Ongoing);
+        assert_eq!(game.get_turn(), Player::First);
+        assert_eq!(game.get_color(), GameTurn::TurnX);
+        assert_eq!(game.get_possible_plays().len(), BOARD_SIZE * BOARD_SIZE);
+    }
+
+    #[test]
+    fn test_first_won_five_in_a_row() {
+        let mut game = game_after(
+            GameRules::default(),
+            &[
+                (7, 3),
+                (8, 3),
+                (7, 4),
+                (8, 4),
+                (7, 5),
+                (8, 5),
+                (7, 6),
+                (8, 6),
+            ],
+        );
+        assert_eq!(game.state, GameState::Ongoing);
+
+        game.play(Move::Place(7, 7)).unwrap();
+        assert_eq!(game.state, GameState::FirstWon);
+        assert!(game.get_possible_plays().is_empty());
+        assert_eq!(game.play(Move::Place(0, 0)), Err(GamePlayError::GameIsOver));
+    }
+
+    #[test]
+    fn test_overline_under_win_rules() {
+        // X fills (7, 3) to (7, 5) and (7, 7) to (7, 8), so that (7, 6) makes six in a row
+        let locations = [
+            (7, 3),
+            (0, 0),
+            (7, 4),
+            (0, 2),
+            (7, 5),
+            (0, 4),
+            (7, 7),
+            (0, 6),
+            (7, 8),
+            (0, 8),
+        ];
+
+        let mut five_or_more = game_after(GameRules::default(), &locations);
+        five_or_more.play(Move::Place(7, 6)).unwrap();
+        assert_eq!(five_or_more.state, GameState::FirstWon);
+
+        let exactly_five = GameRules {
+            win_rule: WinRule::ExactlyFive,
+            ..Default::default()
+        };
+        let mut exactly_five = game_after(exactly_five, &locations);
+        exactly_five.play(Move::Place(7, 6)).unwrap();
+        assert_eq!(exactly_five.state, GameState::Ongoing);
+
+        // Renju forbids the overline to X altogether
+        let renju = game_after(renju_rules(), &locations);
+        assert_eq!(
+            renju.get_played(Move::Place(7, 6)).err(),
+            Some(GamePlayError::ForbiddenMove)
+        );
+        assert!(!renju.get_possible_plays().contains(&Move::Place(7, 6)));
+    }
+
+    #[test]
+    fn test_renju_double_three() {
+        // X at (7, 6), (7, 7) and (6, 8), (5, 8): (7, 8) makes two open threes
+        let locations = [
+            (7, 6),
+            (0, 0),
+            (7, 7),
+            (0, 2),
+            (6, 8),
+            (0, 4),
+            (5, 8),
+            (0, 6),
+        ];
+
+        let game = game_after(renju_rules(), &locations);
+        assert_eq!(
+            game.get_played(Move::Place(7, 8)).err(),
+            Some(GamePlayError::ForbiddenMove)
+        );
+
+        // Without renju rules the same move is allowed
+        let game = game_after(GameRules::default(), &locations);
+        assert!(game.get_played(Move::Place(7, 8)).is_ok());
+    }
+
+    #[test]
+    fn test_renju_closed_three_allowed() {
+        // O closes the horizontal three at (7, 5), so (7, 8) makes a single open three
+        let locations = [
+            (7, 6),
+            (7, 5),
+            (7, 7),
+            (0, 2),
+            (6, 8),
+            (0, 4),
+            (5, 8),
+            (0, 6),
+        ];
+
+        let game = game_after(renju_rules(), &locations);
+        assert!(game.get_played(Move::Place(7, 8)).is_ok());
+    }
+
+    #[test]
+    fn test_renju_double_four() {
+        // X at (7, 4), (7, 5), (7, 6) and (4, 7), (5, 7), (6, 7), each closed on one side by O: (7, 7) makes two
+        // fours
+        let locations = [
+            (7, 4),
+            (7, 3),
+            (7, 5),
+            (3, 7),
+            (7, 6),
+            (0, 0),
+            (4, 7),
+            (0, 2),
+            (5, 7),
+            (0, 4),
+            (6, 7),
+            (0, 6),
+        ];
+
+        let game = game_after(renju_rules(), &locations);
+        assert_eq!(
+            game.get_played(Move::Place(7, 7)).err(),
+            Some(GamePlayError::ForbiddenMove)
+        );
+    }
+
+    #[test]
+    fn test_renju_double_four_in_one_line() {
+        // X at (7, 3), (7, 5), (7, 7) and (7, 9): (7, 6) makes X.XXX.X, with a four on either side of the middle
+        let locations = [
+            (7, 3),
+            (0, 0),
+            (7, 5),
+            (0, 2),
+            (7, 7),
+            (0, 4),
+            (7, 9),
+            (0, 6),
+        ];
+
+        let game = game_after(renju_rules(), &locations);
+        assert_eq!(
+            game.get_played(Move::Place(7, 6)).err(),
+            Some(GamePlayError::ForbiddenMove)
+        );
+    }
+
+    #[test]
+    fn test_renju_five_beats_forbidden_pattern() {
+        // (7, 7) makes exactly five in the row, as well as a four in the column
+        let locations = [
+            (7, 3),
+            (0, 0),
+            (7, 4),
+            (0, 2),
+            (7, 5),
+            (0, 4),
+            (7, 6),
+            (0, 6),
+            (4, 7),
+            (0, 8),
+            (5, 7),
+            (0, 10),
+            (6, 7),
+            (3, 7),
+        ];
+
+        let mut game = game_after(renju_rules(), &locations);
+        game.play(Move::Place(7, 7)).unwrap();
+        assert_eq!(game.state, GameState::FirstWon);
+    }
+
+    #[test]
+    fn test_renju_o_is_never_forbidden() {
+        // O makes the same double three that X is forbidden
+        let locations = [
+            (0, 0),
+            (7, 6),
+            (0, 2),
+            (7, 7),
+            (0, 4),
+            (6, 8),
+            (0, 6),
+            (5, 8),
+            (0, 9),
+        ];
+
+        let game = game_after(renju_rules(), &locations);
+        assert_eq!(game.get_color(), GameTurn::TurnO);
+        assert!(game.get_played(Move::Place(7, 8)).is_ok());
+    }
+
+    #[test]
+    fn test_pro_opening() {
+        let rules = GameRules {
+            opening: Opening::Pro,
+            ..Default::default()
+        };
+
+        let mut game = Game::with_rules(rules);
+        assert_eq!(
+            game.play(Move::Place(0, 0)),
+            Err(GamePlayError::OpeningRule)
+        );
+        assert_eq!(game.get_possible_plays(), vec![Move::Place(7, 7)]);
+
+        game.play(Move::Place(7, 7)).unwrap();
+        game.play(Move::Place(7, 8)).unwrap();
+
+        // The second X stone must stay out of the 5x5 square around the centre
+        assert_eq!(
+            game.play(Move::Place(9, 9)),
+            Err(GamePlayError::OpeningRule)
+        );
+        assert_eq!(
+            game.get_possible_plays().len(),
+            BOARD_SIZE * BOARD_SIZE - 25
+        );
+        game.play(Move::Place(10, 10)).unwrap();
+        assert_eq!(game.get_possible_plays().len(), BOARD_SIZE * BOARD_SIZE - 3);
+    }
+
+    #[test]
+    fn test_swap2_second_player_takes_x() {
+        let rules = GameRules {
+            opening: Opening::Swap2,
+            ..Default::default()
+        };
+        let mut game = game_after(rules, &[(7, 7), (7, 8), (8, 8)]);
+
+        // The second player may take a color or place the extra stones
+        assert_eq!(game.get_turn(), Player::Second);
+        assert_eq!(game.get_x_player(), None);
+        assert_eq!(
+            game.get_possible_plays()[..2],
+            [
+                Move::TakeColor(GameTurn::TurnX),
+                Move::TakeColor(GameTurn::TurnO)
+            ]
+        );
+
+        game.play(Move::TakeColor(GameTurn::TurnX)).unwrap();
+        assert_eq!(game.get_x_player(), Some(Player::Second));
+
+        // O is to play, so the first player places the next stone
+        assert_eq!(game.get_turn(), Player::First);
+        assert_eq!(game.get_color(), GameTurn::TurnO);
+        assert_eq!(
+            game.play(Move::TakeColor(GameTurn::TurnO)),
+            Err(GamePlayError::PlacementExpected)
+        );
+    }
+
+    #[test]
+    fn test_swap2_extra_stones() {
+        let rules = GameRules {
+            opening: Opening::Swap2,
+            ..Default::default()
+        };
+        let mut game = game_after(rules, &[(7, 7), (7, 8), (8, 8)]);
+
+        // The second player places one O stone and one X stone
+        game.play(Move::Place(6, 6)).unwrap();
+        assert_eq!(game.get_turn(), Player::Second);
+        game.play(Move::Place(9, 9)).unwrap();
+
+        // The first player must now take a color
+        assert_eq!(game.get_turn(), Player::First);
+        assert!(game.is_choosing_color());
+        assert_eq!(
+            game.get_possible_plays(),
+            vec![
+                Move::TakeColor(GameTurn::TurnX),
+                Move::TakeColor(GameTurn::TurnO)
+            ]
+        );
+        assert_eq!(
+            game.play(Move::Place(0, 0)),
+            Err(GamePlayError::ColorChoiceExpected)
+        );
+
+        game.play(Move::TakeColor(GameTurn::TurnO)).unwrap();
+        assert_eq!(game.get_x_player(), Some(Player::Second));
+        assert_eq!(game.get_turn(), Player::First);
+    }
+
+    #[test]
+    fn test_swap2_winner_by_seat() {
+        let rules = GameRules {
+            opening: Opening::Swap2,
+            ..Default::default()
+        };
+        let mut game = game_after(rules, &[(7, 3), (8, 3), (7, 4)]);
+        game.play(Move::TakeColor(GameTurn::TurnX)).unwrap();
+
+        // The second player completes the row of X stones started by the first player
+        for location in [(8, 4), (7, 5), (8, 5), (7, 6), (0, 0), (7, 7)] {
+            game.play(Move::Place(location.0, location.1)).unwrap();
+        }
+        assert_eq!(game.state, GameState::SecondWon);
+    }
+
+    #[test]
+    fn test_out_of_bound() {
+        let mut game = Game::new();
+        assert_eq!(
+            game.play(Move::Place(15, 0)),
+            Err(GamePlayError::MarkError(board::BoardMarkError::OutOfBound))
+        );
+        assert_eq!(game.stones, 0);
+    }
+
+    #[test]
+    fn test_mark_twice() {
+        let mut game = game_after(GameRules::default(), &[(7, 7)]);
+        assert_eq!(
+            game.play(Move::Place(7, 7)),
+            Err(GamePlayError::MarkError(
+                board::BoardMarkError::NonEmptyCell
+            ))
+        );
+        assert_eq!(game.get_turn(), Player::Second);
+    }
+}
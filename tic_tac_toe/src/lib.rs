@@ -1,2 +1,4 @@
 pub mod board;
 pub mod game;
+pub mod gomoku;
+pub mod ultimate;
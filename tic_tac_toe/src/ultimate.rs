@@ -0,0 +1,422 @@
+//! Contains functionality for constructing and playing an Ultimate Tic-Tac-Toe game
+//!
+//! The game is played on a 3x3 meta-board whose cells are themselves 3x3 Tic-Tac-Toe boards. Players mark cells of
+//! the 9x9 grid, and the location of the mark inside its sub-board sends the opponent to the sub-board at the same
+//! location of the meta-board. When that sub-board is already decided, the opponent may play in any sub-board
+//! instead. A sub-board is won by getting 3 marks in a row in it, and the game is won by winning 3 sub-boards in a
+//! row on the meta-board. Sub-boards that end in a tie count for nobody.
+//!
+//! Cells are located by (`row_index`, `col_index`) on the 9x9 grid, so that the sub-board of a cell is at
+//! (`row_index / 3`, `col_index / 3`) on the meta-board.
+//!
+//! ## Examples
+//!
+//! ```
+//! use tic_tac_toe::ultimate;
+//!
+//! let mut game = ultimate::Game::new();
+//! game.play(4, 4).unwrap(); // Player X plays at the centre, sending O to the central sub-board
+//! game.play(3, 5).unwrap(); // Player O plays in the central sub-board, sending X to the top right one
+//! assert_eq!(game.get_next_board(), Some((0, 2)));
+//! ```
+
+use crate::board;
+use crate::game::{GameState, GameTurn};
+use std::fmt;
+
+/// Number of rows and columns of a sub-board, and of sub-boards in a row or column of the meta-board
+pub const BOARD_SIZE: usize = 3;
+
+/// Number of rows and columns of the whole grid
+pub const GRID_SIZE: usize = BOARD_SIZE * BOARD_SIZE;
+
+/// Represents the game objects. Interally, it keeps track of:
+/// - the 9 sub-boards, row by row, and the state of each of them
+/// - the meta-board, marked with the winner of every won sub-board
+/// - the sub-board the current player is sent to, if any
+/// - the turn of the current player
+/// - the state of the game (i.e, Ongoing, X won, O won, tie)
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Game {
+    boards: Vec<board::Board>,
+    board_states: Vec<GameState>,
+    meta_board: board::Board,
+    next_board: Option<(usize, usize)>,
+    turn: GameTurn,
+    state: GameState,
+}
+
+/// Represents the possible reasons when failing to play a move
+#[derive(Debug, PartialEq, Eq)]
+pub enum GamePlayError {
+    MarkError(board::BoardMarkError),
+    WrongBoard,
+    BoardIsDecided,
+    GameIsOver,
+}
+
+impl Game {
+    /// Initializes a new `Game` object, in which X may play anywhere
+    pub fn new() -> Self {
+        Game {
+            boards: vec![board::Board::new(); BOARD_SIZE * BOARD_SIZE],
+            board_states: vec![GameState::Ongoing; BOARD_SIZE * BOARD_SIZE],
+            meta_board: board::Board::new(),
+            next_board: None,
+            turn: GameTurn::TurnX,
+            state: GameState::Ongoing,
+        }
+    }
+
+    /// Gets the current state of the game
+    pub fn get_state(&self) -> GameState {
+        self.state
+    }
+
+    /// Returns a boolean indicating whether the game is over
+    pub fn is_over(&self) -> bool {
+        !matches!(self.state, GameState::Ongoing)
+    }
+
+    /// Gets the turn of the current player
+    pub fn get_turn(&self) -> GameTurn {
+        self.turn
+    }
+
+    /// Gets the location on the meta-board of the sub-board the current player must play in, or `None` if the
+    /// current player may play in any undecided sub-board
+    pub fn get_next_board(&self) -> Option<(usize, usize)> {
+        self.next_board
+    }
+
+    /// Gets the meta-board, on which every won sub-board is marked with the mark of its winner
+    pub fn get_meta_board(&self) -> &board::Board {
+        &self.meta_board
+    }
+
+    /// Gets the sub-board at location (`board_row`, `board_col`) of the meta-board
+    pub fn get_board(&self, board_row: usize, board_col: usize) -> &board::Board {
+        &self.boards[board_row * BOARD_SIZE + board_col]
+    }
+
+    /// Gets the state of the sub-board at location (`board_row`, `board_col`) of the meta-board
+    pub fn get_board_state(&self, board_row: usize, board_col: usize) -> GameState {
+        self.board_states[board_row * BOARD_SIZE + board_col]
+    }
+
+    /// Updates the states of the sub-board and of the game after the current player marked the cell at location
+    /// (`row_index`, `col_index`) of the grid. Only lines going through that cell can have been completed, on the
+    /// sub-board as well as on the meta-board
+    fn update_state(&mut self, row_index: usize, col_index: usize) {
+        if self.is_over() {
+            panic!("Cannot update state when game is terminated!")
+        }
+
+        let (board_row, board_col) = (row_index / BOARD_SIZE, col_index / BOARD_SIZE);
+        let board_index = board_row * BOARD_SIZE + board_col;
+        let sub_board = &self.boards[board_index];
+
+        let (mark, won_state) = match self.turn {
+            GameTurn::TurnX => (board::Cell::X, GameState::XWon),
+            GameTurn::TurnO => (board::Cell::O, GameState::OWon),
+        };
+
+        if sub_board.get_longest_line(row_index % BOARD_SIZE, col_index % BOARD_SIZE) >= BOARD_SIZE
+        {
+            self.board_states[board_index] = won_state;
+            self.meta_board.mark(mark, board_row, board_col).unwrap();
+
+            if self.meta_board.get_longest_line(board_row, board_col) >= BOARD_SIZE {
+                self.state = won_state;
+                return;
+            }
+        } else if sub_board.is_full() {
+            self.board_states[board_index] = GameState::Tie;
+        }
+
+        if !self.board_states.contains(&GameState::Ongoing) {
+            self.state = GameState::Tie;
+        }
+    }
+
+    /// Plays one turn of Ultimate Tic-Tac-Toe as the current player by marking the cell at location
+    /// (`row_index`, `col_index`) of the grid. Returns an `Err` if:
+    /// - location is out-of-bounds, or
+    /// - cell played is non-empty, or
+    /// - cell played is outside the sub-board the current player is sent to, or
+    /// - cell played is in an already decided sub-board, or
+    /// - game is terminated (not `Ongoing`)
+    pub fn play(&mut self, row_index: usize, col_index: usize) -> Result<(), GamePlayError> {
+        if self.is_over() {
+            return Err(GamePlayError::GameIsOver);
+        }
+
+        if row_index >= GRID_SIZE || col_index >= GRID_SIZE {
+            return Err(GamePlayError::MarkError(board::BoardMarkError::OutOfBound));
+        }
+
+        let (board_row, board_col) = (row_index / BOARD_SIZE, col_index / BOARD_SIZE);
+        if self
+            .next_board
+            .is_some_and(|next_board| next_board != (board_row, board_col))
+        {
+            return Err(GamePlayError::WrongBoard);
+        }
+
+        let board_index = board_row * BOARD_SIZE + board_col;
+        if self.board_states[board_index] != GameState::Ongoing {
+            return Err(GamePlayError::BoardIsDecided);
+        }
+
+        let (mark, next_turn) = match self.turn {
+            GameTurn::TurnX => (board::Cell::X, GameTurn::TurnO),
+            GameTurn::TurnO => (board::Cell::O, GameTurn::TurnX),
+        };
+
+        self.boards[board_index]
+            .mark(mark, row_index % BOARD_SIZE, col_index % BOARD_SIZE)
+            .map_err(GamePlayError::MarkError)?;
+
+        self.update_state(row_index, col_index);
+
+        let sent_to = (row_index % BOARD_SIZE, col_index % BOARD_SIZE);
+        self.next_board = match self.get_board_state(sent_to.0, sent_to.1) {
+            GameState::Ongoing if !self.is_over() => Some(sent_to),
+            _ => None,
+        };
+        self.turn = next_turn;
+        Ok(())
+    }
+
+    /// Returns a copy of the game state after the move (row_index, col_index) has been played
+    pub fn get_played(&self, row_index: usize, col_index: usize) -> Result<Self, GamePlayError> {
+        let mut cloned_game = (*self).clone();
+        cloned_game.play(row_index, col_index)?;
+        Ok(cloned_game)
+    }
+
+    // Returns a vector of possible moves as (row_index, col_index) on the grid.
+    // List of moves is always ordered upper left -> bottom right
+    pub fn get_possible_plays(&self) -> Vec<(usize, usize)> {
+        if self.is_over() {
+            return Vec::new();
+        }
+
+        (0..GRID_SIZE)
+            .flat_map(|row_index| (0..GRID_SIZE).map(move |col_index| (row_index, col_index)))
+            .filter(|&(row_index, col_index)| {
+                let (board_row, board_col) = (row_index / BOARD_SIZE, col_index / BOARD_SIZE);
+                self.next_board
+                    .is_none_or(|next_board| next_board == (board_row, board_col))
+                    && self.get_board_state(board_row, board_col) == GameState::Ongoing
+                    && self
+                        .get_board(board_row, board_col)
+                        .get_cell(row_index % BOARD_SIZE, col_index % BOARD_SIZE)
+                        .unwrap()
+                        == board::Cell::Empty
+            })
+            .collect()
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let game_state = match self.state {
+            GameState::Ongoing => "Ongoing",
+            GameState::XWon => "X Won",
+            GameState::OWon => "O Won",
+            GameState::Tie => "Tie",
+        };
+
+        let game_turn = match self.turn {
+            GameTurn::TurnX => "X",
+            GameTurn::TurnO => "O",
+        };
+
+        let next_board = match self.next_board {
+            Some((board_row, board_col)) => format!("({}, {})", board_row, board_col),
+            None => "Any".to_string(),
+        };
+
+        writeln!(
+            f,
+            "Game State: {}, Player Turn: {}, Next Board: {}",
+            game_state, game_turn, next_board
+        )?;
+        writeln!(f)?;
+
+        for row_index in 0..GRID_SIZE {
+            if row_index > 0 && row_index % BOARD_SIZE == 0 {
+                writeln!(
+                    f,
+                    "{}",
+                    vec!["-".repeat(2 * BOARD_SIZE + 1); BOARD_SIZE].join("+")
+                )?;
+            }
+
+            for col_index in 0..GRID_SIZE {
+                if col_index > 0 && col_index % BOARD_SIZE == 0 {
+                    write!(f, " |")?;
+                }
+
+                let cell = self
+                    .get_board(row_index / BOARD_SIZE, col_index / BOARD_SIZE)
+                    .get_cell(row_index % BOARD_SIZE, col_index % BOARD_SIZE)
+                    .unwrap();
+                let symbol = match cell {
+                    board::Cell::X => "X",
+                    board::Cell::O => "O",
+                    board::Cell::Empty => ".",
+                };
+                write!(f, " {}", symbol)?;
+            }
+
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays `moves` from an empty grid
+    fn game_after(moves: &[(usize, usize)]) -> Game {
+        let mut game = Game::new();
+        for &(row_index, col_index) in moves {
+            game.play(row_index, col_index).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_initialize_game() {
+        let game = Game::new();
+        assert_eq!(game.state, GameState::Ongoing);
+        assert_eq!(game.turn, GameTurn::TurnX);
+        assert_eq!(game.next_board, None);
+        assert_eq!(game.get_possible_plays().len(), GRID_SIZE * GRID_SIZE);
+    }
+
+    #[test]
+    fn test_sent_to_board() {
+        // X plays the top right cell of the central sub-board, sending O to the top right sub-board
+        let mut game = game_after(&[(3, 5)]);
+        assert_eq!(game.get_next_board(), Some((0, 2)));
+        assert_eq!(
+            game.get_possible_plays(),
+            (0..3)
+                .flat_map(|row_index| (6..9).map(move |col_index| (row_index, col_index)))
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(game.play(4, 4), Err(GamePlayError::WrongBoard));
+        assert_eq!(game.turn, GameTurn::TurnO);
+
+        game.play(1, 7).unwrap();
+        assert_eq!(game.get_next_board(), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_sub_board_won() {
+        // X takes the middle row of the top left sub-board while O keeps sending X back there
+        let game = game_after(&[(1, 0), (3, 0), (1, 1), (3, 3), (1, 2)]);
+
+        assert_eq!(game.get_board_state(0, 0), GameState::XWon);
+        assert_eq!(
+            game.get_meta_board().get_cell(0, 0).unwrap(),
+            board::Cell::X
+        );
+        assert_eq!(game.get_next_board(), Some((1, 2)));
+        assert_eq!(game.state, GameState::Ongoing);
+    }
+
+    #[test]
+    fn test_free_choice_when_sent_to_decided_board() {
+        // Once X won the top left sub-board, O sends X back to it
+        let mut game = game_after(&[(1, 0), (3, 0), (1, 1), (3, 3), (1, 2), (3, 6)]);
+        assert_eq!(game.get_next_board(), None);
+
+        assert_eq!(game.play(0, 0), Err(GamePlayError::BoardIsDecided));
+        assert_eq!(game.get_possible_plays().len(), 81 - 9 - 3);
+        assert!(game
+            .get_possible_plays()
+            .iter()
+            .all(|&(row_index, col_index)| row_index >= 3 || col_index >= 3));
+
+        game.play(8, 8).unwrap();
+        assert_eq!(game.get_next_board(), Some((2, 2)));
+    }
+
+    #[test]
+    fn test_x_won_meta_board() {
+        // X takes the middle row of each sub-board of the top row of the meta-board, while O wins the two
+        // sub-boards it is sent to on the way
+        let mut game = game_after(&[
+            (1, 0),
+            (3, 0),
+            (1, 1),
+            (3, 3),
+            (1, 2),
+            (3, 7),
+            (1, 3),
+            (3, 1),
+            (1, 4),
+            (3, 4),
+            (1, 5),
+            (3, 8),
+            (1, 6),
+            (3, 2),
+            (1, 7),
+            (3, 5),
+        ]);
+        assert_eq!(game.get_board_state(1, 0), GameState::OWon);
+        assert_eq!(game.get_board_state(1, 1), GameState::OWon);
+        assert_eq!(game.state, GameState::Ongoing);
+
+        game.play(1, 8).unwrap();
+        assert_eq!(game.state, GameState::XWon);
+        assert!(game.get_possible_plays().is_empty());
+        assert_eq!(game.play(4, 4), Err(GamePlayError::GameIsOver));
+    }
+
+    #[test]
+    fn test_out_of_bound() {
+        let mut game = Game::new();
+        assert_eq!(
+            game.play(9, 0),
+            Err(GamePlayError::MarkError(board::BoardMarkError::OutOfBound))
+        );
+        assert_eq!(game.turn, GameTurn::TurnX);
+    }
+
+    #[test]
+    fn test_mark_twice() {
+        // O is sent back to the central sub-board and tries the cell X just marked
+        let mut game = game_after(&[(4, 4)]);
+        assert_eq!(
+            game.play(4, 4),
+            Err(GamePlayError::MarkError(
+                board::BoardMarkError::NonEmptyCell
+            ))
+        );
+        assert_eq!(game.turn, GameTurn::TurnO);
+    }
+
+    #[test]
+    fn test_get_played() {
+        let game = game_after(&[(4, 4)]);
+        let played = game.get_played(3, 3).unwrap();
+
+        assert_eq!(played.get_next_board(), Some((0, 0)));
+        // The original game is left untouched
+        assert_eq!(game.get_next_board(), Some((1, 1)));
+    }
+}